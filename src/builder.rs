@@ -0,0 +1,193 @@
+use crate::extras::Extras;
+use crate::lp_errors::LpErrors;
+use crate::{Balas, Model};
+use lp_parser_rs::model::coefficient::Coefficient;
+use lp_parser_rs::model::constraint::Constraint;
+use lp_parser_rs::model::lp_problem::LPProblem;
+use lp_parser_rs::model::objective::Objective;
+use lp_parser_rs::model::sense::{Cmp, Sense};
+use lp_parser_rs::model::variable::Variable;
+use std::collections::{HashMap, HashSet};
+
+/// Builds a problem in code rather than reading it from a file.  Variables,
+/// the objective and the constraints can be given in any order and in their natural
+/// form; `build` applies the same normalization as the file readers, and the
+/// solutions of the resulting solver are in terms of the names given here.
+///
+/// ```no_run
+/// use balas::{Cmp, LpErrors, ModelBuilder, Sense};
+///
+/// fn main() -> Result<(), LpErrors> {
+///     let mut balas = ModelBuilder::new("knapsack")
+///         .binaries(["x", "y", "z"])
+///         .objective(Sense::Maximize, [("x", 5.0), ("y", 3.0), ("z", 7.0)])
+///         .constraint("weight", [("x", 4.0), ("y", 2.0), ("z", 5.0)], Cmp::LessOrEqual, 8.0)
+///         .build()?;
+///     balas.solve();
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ModelBuilder {
+    name: String,
+    sense: Sense,
+    variables: Vec<String>,
+    objective: Vec<Coefficient>,
+    constant: f64,
+    constraints: Vec<(String, Vec<Coefficient>, Cmp, f64)>,
+    /// The upper side of each range, whose constraint is on the lower side
+    ranges: HashMap<String, f64>,
+}
+
+/// Combine the terms into coefficients, adding up repeated variables
+fn coefficients<'a>(terms: impl IntoIterator<Item = (&'a str, f64)>) -> Vec<Coefficient> {
+    let mut coefficients: Vec<Coefficient> = vec![];
+    for (var, coefficient) in terms {
+        match coefficients.iter_mut().find(|c| c.var_name == var) {
+            Some(c) => c.coefficient += coefficient,
+            None => coefficients.push(Coefficient {
+                var_name: var.to_string(),
+                coefficient,
+            }),
+        }
+    }
+    coefficients
+}
+
+impl ModelBuilder {
+    pub fn new(name: &str) -> ModelBuilder {
+        ModelBuilder {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Add a binary variable
+    pub fn binary(mut self, name: &str) -> Self {
+        self.variables.push(name.to_string());
+        self
+    }
+
+    /// Add several binary variables
+    pub fn binaries<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        self.variables
+            .extend(names.into_iter().map(|name| name.to_string()));
+        self
+    }
+
+    /// Set the objective.  Variables that are left out have a zero cost.
+    pub fn objective<'a>(
+        mut self,
+        sense: Sense,
+        terms: impl IntoIterator<Item = (&'a str, f64)>,
+    ) -> Self {
+        self.sense = sense;
+        self.objective = coefficients(terms);
+        self
+    }
+
+    /// Add a constant to the objective
+    pub fn objective_constant(mut self, constant: f64) -> Self {
+        self.constant += constant;
+        self
+    }
+
+    /// Add the constraint `terms sense rhs`
+    pub fn constraint<'a>(
+        mut self,
+        name: &str,
+        terms: impl IntoIterator<Item = (&'a str, f64)>,
+        sense: Cmp,
+        rhs: f64,
+    ) -> Self {
+        self.constraints
+            .push((name.to_string(), coefficients(terms), sense, rhs));
+        self
+    }
+
+    /// Add the constraint `lower <= terms <= upper`, which the solver keeps as a single
+    /// row
+    pub fn range<'a>(
+        mut self,
+        name: &str,
+        terms: impl IntoIterator<Item = (&'a str, f64)>,
+        lower: f64,
+        upper: f64,
+    ) -> Self {
+        self.constraints.push((
+            name.to_string(),
+            coefficients(terms),
+            Cmp::GreaterOrEqual,
+            lower,
+        ));
+        self.ranges.insert(name.to_string(), upper);
+        self
+    }
+
+    /// What the problem has beyond the `LPProblem`
+    fn extras(&self) -> Extras {
+        Extras {
+            objective_constant: self.constant,
+            ranges: self.ranges.clone(),
+            fixed: HashMap::new(),
+        }
+    }
+
+    /// Check the names and assemble the problem, as the readers would have parsed it
+    fn to_lp_problem(&self) -> Result<LPProblem, LpErrors> {
+        let mut variables = HashMap::new();
+        for var in &self.variables {
+            if variables.insert(var.clone(), Variable::Binary).is_some() {
+                return Err(LpErrors::DuplicateName(var.clone()));
+            }
+        }
+        let terms = self
+            .objective
+            .iter()
+            .chain(self.constraints.iter().flat_map(|(_, terms, ..)| terms));
+        for c in terms {
+            if !variables.contains_key(&c.var_name) {
+                return Err(LpErrors::UnknownVariable(c.var_name.clone()));
+            }
+        }
+
+        let mut names = HashSet::new();
+        let mut constraints = HashMap::new();
+        for (name, coefficients, sense, rhs) in &self.constraints {
+            if !names.insert(name) {
+                return Err(LpErrors::DuplicateName(name.clone()));
+            }
+            constraints.insert(
+                name.clone(),
+                Constraint::Standard {
+                    name: name.clone(),
+                    coefficients: coefficients.clone(),
+                    sense: sense.clone(),
+                    rhs: *rhs,
+                },
+            );
+        }
+
+        Ok(LPProblem {
+            problem_name: self.name.clone(),
+            problem_sense: self.sense.clone(),
+            variables,
+            objectives: vec![Objective {
+                name: "obj".to_string(),
+                coefficients: self.objective.clone(),
+            }],
+            constraints,
+        })
+    }
+
+    /// Normalize the problem and create the solver for it.  The solver's `transform`
+    /// maps its results back to this problem.
+    pub fn build(&self) -> Result<Balas<f64>, LpErrors> {
+        Balas::from_lp_problem(&self.to_lp_problem()?, &self.extras())
+    }
+
+    /// The problem as given, for checking solutions with `Model::verify_solution`
+    pub fn model(&self) -> Result<Model, LpErrors> {
+        Ok(Model::new(self.to_lp_problem()?, self.extras()))
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{NodeState, SearchRecording};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// The fill color of a node with this outcome
+fn color(state: NodeState) -> &'static str {
+    match state {
+        NodeState::Default | NodeState::Active | NodeState::Visited => "white",
+        NodeState::Fathomed => "palegreen",
+        NodeState::Infeasible => "lightpink",
+        NodeState::Suboptimal => "khaki",
+        NodeState::ImpossibleChildren => "salmon",
+        NodeState::Skipped => "lightblue",
+    }
+}
+
+/// Quote a string for a DOT label, keeping its line breaks
+fn quoted(text: &str) -> String {
+    let text = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{text}\"")
+}
+
+/// The nodes of a collapsed subtree
+#[derive(Default)]
+struct Collapsed {
+    nodes: usize,
+    fathomed: usize,
+    incumbent: bool,
+}
+
+/// Write a recorded search tree as a Graphviz DOT graph.  The nodes are colored by
+/// their outcome, the incumbents have a heavy double border, and each edge is labeled
+/// with the variable it sets and its value in the original problem.  Below
+/// `collapse_depth` (the number of variables set), each subtree is drawn as a single
+/// node that counts what it holds.
+pub fn write_dot(
+    recording: &SearchRecording,
+    collapse_depth: Option<usize>,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    writeln!(out, "digraph search {{")?;
+    writeln!(
+        out,
+        "  node [shape=box, style=filled, fontname=\"Helvetica\"];"
+    )?;
+    writeln!(out, "  edge [fontname=\"Helvetica\", fontsize=10];")?;
+
+    // the DOT id of each node drawn, by its path
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    let mut collapsed: HashMap<&str, Collapsed> = HashMap::new();
+    for (id, record) in recording.records.iter().enumerate() {
+        let depth = record.node.len();
+        if let Some(max) = collapse_depth {
+            if depth > max {
+                let subtree = collapsed.entry(&record.node[..max]).or_default();
+                subtree.nodes += 1;
+                subtree.fathomed += usize::from(record.state == NodeState::Fathomed);
+                subtree.incumbent |= record.incumbent;
+                continue;
+            }
+        }
+
+        let objective = recording.transform.objective(record.objective);
+        let mut label = format!("{:?}\nobjective {objective}", record.state);
+        if record.violated > 0 {
+            label += &format!("\n{} violated", record.violated);
+        }
+        let highlight = if record.incumbent {
+            ", color=darkgreen, penwidth=3, peripheries=2"
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "  n{id} [label={}, fillcolor={}{highlight}];",
+            quoted(&label),
+            color(record.state)
+        )?;
+        if let Some(parent) = depth
+            .checked_sub(1)
+            .and_then(|d| ids.get(&record.node[..d]))
+        {
+            let column = depth - 1;
+            let value = if record.node.ends_with('1') { 1 } else { 0 };
+            let edge = format!(
+                "{} = {}",
+                recording.columns[column],
+                recording.value(column, value)
+            );
+            writeln!(out, "  n{parent} -> n{id} [label={}];", quoted(&edge))?;
+        }
+        ids.insert(&record.node, id);
+    }
+
+    let mut collapsed: Vec<_> = collapsed.into_iter().collect();
+    collapsed.sort_by_key(|(path, _)| ids.get(path).copied());
+    for (i, (path, subtree)) in collapsed.iter().enumerate() {
+        let label = format!(
+            "{} more nodes\n{} fathomed",
+            subtree.nodes, subtree.fathomed
+        );
+        let highlight = if subtree.incumbent {
+            ", color=darkgreen, penwidth=3"
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "  c{i} [label={}, shape=ellipse, style=dashed{highlight}];",
+            quoted(&label)
+        )?;
+        if let Some(parent) = ids.get(path) {
+            writeln!(out, "  n{parent} -> c{i} [style=dashed];")?;
+        }
+    }
+    writeln!(out, "}}")
+}
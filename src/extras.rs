@@ -0,0 +1,329 @@
+use crate::lp_errors::LpErrors;
+use anyhow::anyhow;
+use std::collections::HashMap;
+
+/// What a problem can have that the `LPProblem` of `lp_parser_rs` cannot hold.  The
+/// readers and the builder fill this in next to the `LPProblem`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Extras {
+    /// Constant term of the objective
+    pub(crate) objective_constant: f64,
+    /// The upper side of each range row, by name.  The row itself is a `>=` constraint
+    /// on its lower side.
+    pub(crate) ranges: HashMap<String, f64>,
+    /// Variables whose bounds leave them only one binary value
+    pub(crate) fixed: HashMap<String, u8>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Less,
+    Greater,
+    Equal,
+}
+
+impl Comparison {
+    /// The same comparison with its sides swapped
+    fn flipped(self) -> Comparison {
+        match self {
+            Comparison::Less => Comparison::Greater,
+            Comparison::Greater => Comparison::Less,
+            Comparison::Equal => Comparison::Equal,
+        }
+    }
+}
+
+/// Split text at its comparison operators (`<`, `<=`, `=<`, `>`, `>=`, `=>` and `=`)
+fn split_comparisons(text: &str) -> (Vec<&str>, Vec<Comparison>) {
+    let mut parts = vec![];
+    let mut comparisons = vec![];
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if !matches!(c, '<' | '>' | '=') {
+            continue;
+        }
+        let mut operator = c.to_string();
+        if let Some(&(_, next)) = chars.peek() {
+            if matches!(next, '<' | '>' | '=') {
+                operator.push(next);
+                chars.next();
+            }
+        }
+        comparisons.push(if operator.contains('<') {
+            Comparison::Less
+        } else if operator.contains('>') {
+            Comparison::Greater
+        } else {
+            Comparison::Equal
+        });
+        parts.push(&text[start..i]);
+        start = i + operator.len();
+    }
+    parts.push(&text[start..]);
+    (parts, comparisons)
+}
+
+fn lp_error(line: usize, reason: String) -> LpErrors {
+    LpErrors::LPParseError(anyhow!("line {line}: {reason}"))
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    text.split_whitespace().collect::<String>().parse().ok()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Other,
+    Constraints,
+    Bounds,
+}
+
+/// The section headers of an LP file, and the sections they start
+const HEADERS: [(&str, Section); 26] = [
+    ("subject to", Section::Constraints),
+    ("such that", Section::Constraints),
+    ("st", Section::Constraints),
+    ("s.t.", Section::Constraints),
+    ("bounds", Section::Bounds),
+    ("bound", Section::Bounds),
+    ("minimize", Section::Other),
+    ("minimise", Section::Other),
+    ("minimum", Section::Other),
+    ("min", Section::Other),
+    ("maximize", Section::Other),
+    ("maximise", Section::Other),
+    ("maximum", Section::Other),
+    ("max", Section::Other),
+    ("binaries", Section::Other),
+    ("binary", Section::Other),
+    ("bin", Section::Other),
+    ("generals", Section::Other),
+    ("general", Section::Other),
+    ("gen", Section::Other),
+    ("integers", Section::Other),
+    ("semi-continuous", Section::Other),
+    ("semis", Section::Other),
+    ("semi", Section::Other),
+    ("sos", Section::Other),
+    ("end", Section::Other),
+];
+
+/// If the text is a section header (in any case, and possibly followed by a colon and
+/// more of the section), the section and where the rest of the text starts
+fn split_header(text: &str) -> Option<(Section, usize)> {
+    let lower = text.to_ascii_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    HEADERS.iter().find_map(|&(header, section)| {
+        let keywords: Vec<&str> = header.split(' ').collect();
+        let (last, first) = keywords.split_last()?;
+        let word = *words.get(first.len())?;
+        let whole = word == *last && words.len() == keywords.len();
+        let colon = word.strip_suffix(':') == Some(last);
+        if !(words.starts_with(first) && (whole || colon)) {
+            return None;
+        }
+        // the words are slices of `lower`, which has the same layout as `text`
+        Some((
+            section,
+            word.as_ptr() as usize - lower.as_ptr() as usize + word.len(),
+        ))
+    })
+}
+
+/// Take what `lp_parser_rs` does not understand out of the code of an LP file: range
+/// rows (`name: -5 <= x + y <= 10`, which have to be named and on one line) become
+/// `>=` rows on their lower side, and the bounds section is removed, keeping the
+/// variables that the bounds fix.  Returns the rest of the code for the parser.
+pub(crate) fn split_lp_extras(code: &str) -> Result<(String, Extras), LpErrors> {
+    let mut extras = Extras::default();
+    // the binary values each bounded variable may still take: (0 allowed, 1 allowed)
+    let mut allowed: HashMap<String, (bool, bool)> = HashMap::new();
+    let mut section = Section::Other;
+    let mut rest = String::with_capacity(code.len());
+    for (i, line) in code.lines().enumerate() {
+        let line_no = i + 1;
+        let mut text = line.split('\\').next().unwrap_or_default().trim();
+        let mut line = line;
+        if let Some((next, end)) = split_header(text) {
+            // the header goes on a line of its own, which the parser always reads
+            section = next;
+            if section != Section::Bounds {
+                rest.push_str(text[..end].trim_end_matches(':'));
+                rest.push('\n');
+            }
+            text = text[end..].trim();
+            if text.is_empty() {
+                continue;
+            }
+            line = text;
+        }
+
+        match section {
+            Section::Bounds => {
+                let (parts, comparisons) = split_comparisons(text);
+                // (variable, comparison, value) in that order
+                let bounds = match (parts.as_slice(), comparisons.as_slice()) {
+                    // `x free` and the like say nothing about a binary
+                    (_, []) => vec![],
+                    ([left, right], &[comparison]) => match parse_number(right) {
+                        Some(value) => vec![(left.trim(), comparison, value)],
+                        None => {
+                            let value = parse_number(left).ok_or_else(|| {
+                                lp_error(line_no, format!("cannot read the bound \"{text}\""))
+                            })?;
+                            vec![(right.trim(), comparison.flipped(), value)]
+                        }
+                    },
+                    ([left, var, right], &[first, second]) => {
+                        let (Some(lower), Some(upper)) = (parse_number(left), parse_number(right))
+                        else {
+                            return Err(lp_error(
+                                line_no,
+                                format!("cannot read the bound \"{text}\""),
+                            ));
+                        };
+                        vec![
+                            (var.trim(), first.flipped(), lower),
+                            (var.trim(), second, upper),
+                        ]
+                    }
+                    _ => {
+                        return Err(lp_error(
+                            line_no,
+                            format!("cannot read the bound \"{text}\""),
+                        ))
+                    }
+                };
+                for (var, comparison, value) in bounds {
+                    let (zero, one) = allowed.entry(var.to_string()).or_insert((true, true));
+                    let allows = |x: f64| match comparison {
+                        Comparison::Less => x <= value,
+                        Comparison::Greater => x >= value,
+                        Comparison::Equal => x == value,
+                    };
+                    *zero &= allows(0.0);
+                    *one &= allows(1.0);
+                }
+                continue;
+            }
+            Section::Constraints => {
+                let (parts, comparisons) = split_comparisons(text);
+                match (parts.as_slice(), comparisons.as_slice()) {
+                    ([left, expression, right], &[first, second]) => {
+                        let Some((name, left)) = left.split_once(':') else {
+                            return Err(lp_error(
+                                line_no,
+                                format!("a range needs a name: \"{text}\""),
+                            ));
+                        };
+                        let (Some(left), Some(right)) = (parse_number(left), parse_number(right))
+                        else {
+                            return Err(lp_error(
+                                line_no,
+                                format!("a range needs a number on each side: \"{text}\""),
+                            ));
+                        };
+                        let (lower, upper) = match (first, second) {
+                            (Comparison::Less, Comparison::Less) => (left, right),
+                            (Comparison::Greater, Comparison::Greater) => (right, left),
+                            _ => {
+                                return Err(lp_error(
+                                    line_no,
+                                    format!(
+                                        "a range needs two comparisons the same way: \"{text}\""
+                                    ),
+                                ))
+                            }
+                        };
+                        let name = name.trim();
+                        extras.ranges.insert(name.to_string(), upper);
+                        rest.push_str(&format!("{name}: {} >= {lower}\n", expression.trim()));
+                        continue;
+                    }
+                    // a number before the only comparison starts a range that goes on
+                    // past this line
+                    ([left, _], [_]) => {
+                        let left = left.split_once(':').map_or(*left, |(_, left)| left);
+                        if parse_number(left).is_some() {
+                            return Err(lp_error(
+                                line_no,
+                                format!("a range has to be on one line: \"{text}\""),
+                            ));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Section::Other => {}
+        }
+        rest.push_str(line);
+        rest.push('\n');
+    }
+
+    for (var, allowed) in allowed {
+        match allowed {
+            (true, true) => {}
+            (true, false) => {
+                extras.fixed.insert(var, 0);
+            }
+            (false, true) => {
+                extras.fixed.insert(var, 1);
+            }
+            (false, false) => return Err(LpErrors::InfeasibleBound(var)),
+        }
+    }
+    Ok((rest, extras))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Balas;
+    use lp_parser_rs::parse::parse_lp_file;
+
+    #[test]
+    fn turns_ranges_into_rows_on_their_lower_side() {
+        let code = "Maximize\n obj: x + y + z\nSubject To:\n both: 1 <= x + y + z <= 2\n c2: 4 >= x - z >= -1\nBinaries\n x y z\nEnd\n";
+        let (rest, extras) = split_lp_extras(code).unwrap();
+        assert!(rest.contains("both: x + y + z >= 1\n"));
+        assert!(rest.contains("c2: x - z >= -1\n"));
+        assert_eq!(extras.ranges["both"], 2.0);
+        assert_eq!(extras.ranges["c2"], 4.0);
+
+        let lp = parse_lp_file(&rest).unwrap();
+        let mut balas = Balas::from_lp_problem(&lp, &extras).unwrap();
+        assert_eq!(balas.solve().solution.unwrap().objective, 2.0);
+    }
+
+    #[test]
+    fn reads_headers_with_a_colon() {
+        let code = "Minimize\n x\nsuch that: c1: -1 <= x - y <= 1\nbounds:\n y = 1\nEnd\n";
+        let (rest, extras) = split_lp_extras(code).unwrap();
+        assert!(rest.contains("such that\nc1: x - y >= -1\n"));
+        assert!(!rest.contains("bounds"));
+        assert_eq!(extras.ranges["c1"], 1.0);
+        assert_eq!(extras.fixed["y"], 1);
+    }
+
+    #[test]
+    fn needs_a_name_for_a_range() {
+        let code = "Minimize\n x\nSubject To\n c1: x + y >= 1\n 0 <= x - y <= 1\nEnd\n";
+        assert!(split_lp_extras(code).is_err());
+    }
+
+    #[test]
+    fn needs_a_range_on_one_line() {
+        let code = "Minimize\n x\nSubject To\n r: -1 <= x\n - y <= 1\nEnd\n";
+        assert!(split_lp_extras(code).is_err());
+    }
+
+    #[test]
+    fn rejects_bounds_that_allow_no_binary_value() {
+        let code = "Minimize\n x\nSubject To\n c1: x >= 0\nBounds\n 0.2 <= x <= 0.8\nEnd\n";
+        assert!(matches!(
+            split_lp_extras(code),
+            Err(LpErrors::InfeasibleBound(var)) if var == "x"
+        ));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::lp_errors::LpErrors;
+use crate::{Balas, Transform};
+use num::{Bounded, FromPrimitive};
+use std::path::Path;
+use std::{fmt::Display, ops::Neg};
+
+/// The largest denominator tried when looking for the fraction a coefficient stands for
+const MAX_DENOMINATOR: f64 = (1 << 20) as f64;
+
+/// How closely (relative to its size) a fraction has to match a coefficient
+const TOLERANCE: f64 = 1e-9;
+
+/// The denominator of the simplest fraction that `value` is (within `TOLERANCE`), found
+/// from its continued fraction
+fn denominator(value: f64) -> Option<i128> {
+    if !value.is_finite() {
+        return None;
+    }
+    let (mut numerator, mut previous_numerator) = (1.0, 0.0);
+    let (mut denominator, mut previous_denominator) = (0.0, 1.0);
+    let mut rest = value;
+    loop {
+        let whole = rest.floor();
+        (numerator, previous_numerator) = (whole * numerator + previous_numerator, numerator);
+        (denominator, previous_denominator) =
+            (whole * denominator + previous_denominator, denominator);
+        if denominator > MAX_DENOMINATOR {
+            return None;
+        }
+        if (value - numerator / denominator).abs() <= TOLERANCE * value.abs().max(1.0) {
+            return Some(denominator as i128);
+        }
+        rest = 1.0 / (rest - whole);
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Whether `value` is finite and small enough to become an `i128`
+fn fits(value: f64) -> bool {
+    value.is_finite() && value.abs() < i128::MAX as f64
+}
+
+/// The value as a whole number after scaling it by `scale`, if it is one.  Uses the
+/// same tolerance as `denominator`, scaled along with the value.
+fn scaled(value: f64, scale: i128) -> Option<i128> {
+    let scale = scale as f64;
+    let whole = (value * scale).round();
+    (fits(whole) && (value * scale - whole).abs() <= TOLERANCE * value.abs().max(1.0) * scale)
+        .then_some(whole as i128)
+}
+
+/// The smallest factor that makes all of the values whole numbers.  Returns the index
+/// of the first value there is none for.
+fn common_scale(values: &[f64]) -> Result<i128, usize> {
+    let mut scale: i128 = 1;
+    for (i, &value) in values.iter().enumerate() {
+        let d = denominator(value).ok_or(i)?;
+        scale = (scale / gcd(scale, d)).checked_mul(d).ok_or(i)?;
+    }
+    Ok(scale)
+}
+
+impl Balas<f64> {
+    /// Convert to a solver that uses exact integer arithmetic, such as `Balas<i64>`.
+    /// Each constraint is scaled by the common denominator of its coefficients,
+    /// right-hand side and range, so these can be fractions (up to a denominator of
+    /// about a million), but the objective coefficients and constant have to be whole
+    /// numbers so that the objective values are unchanged.  The conversion fails if a
+    /// sum that the search could reach might overflow `I`.
+    ///
+    /// Only the problem is converted, so this is best done right after reading it
+    /// (`presolve` and `set_branch_order` can come before or after).
+    pub fn to_integer<I>(&self) -> Result<Balas<I>, LpErrors>
+    where
+        I: Bounded
+            + Neg<Output = I>
+            + Copy
+            + Display
+            + num::Zero
+            + num::ToPrimitive
+            + FromPrimitive
+            + for<'a> std::ops::AddAssign<&'a I>
+            + for<'a> std::ops::SubAssign<&'a I>
+            + std::cmp::PartialOrd
+            + std::fmt::Debug,
+        Vec<I>: FromIterator<<I as Neg>::Output>,
+    {
+        let max = I::max_value().to_i128().unwrap_or(i128::MAX);
+        let name = |column: usize| &self.vars[self.order[column]];
+        let convert = |value: i128| I::from_i128(value).expect("checked against the sums");
+
+        let objective_overflow = || LpErrors::IntegerOverflow("the objective".to_string());
+
+        let mut coefficients = vec![];
+        let mut total: i128 = 0;
+        for (column, &cost) in self.coefficients.iter().enumerate() {
+            if cost.is_finite() && !fits(cost) {
+                return Err(objective_overflow());
+            }
+            let cost = scaled(cost, 1).ok_or_else(|| {
+                LpErrors::NotIntegral(format!("objective coefficient of {}", name(column)))
+            })?;
+            total = cost
+                .checked_abs()
+                .and_then(|cost| total.checked_add(cost))
+                .ok_or_else(objective_overflow)?;
+            coefficients.push(cost);
+        }
+        if self.transform.offset.is_finite() && !fits(self.transform.offset) {
+            return Err(objective_overflow());
+        }
+        let offset = scaled(self.transform.offset, 1)
+            .ok_or_else(|| LpErrors::NotIntegral("objective constant".to_string()))?;
+        match offset
+            .checked_abs()
+            .and_then(|offset| total.checked_add(offset))
+        {
+            Some(total) if total <= max => {}
+            _ => return Err(objective_overflow()),
+        }
+
+        let mut constraints = vec![vec![I::zero(); self.rhs.len()]; self.coefficients.len()];
+        let mut rhs = vec![];
+        let mut ranges = vec![];
+        for (row, &b) in self.rhs.iter().enumerate() {
+            let range = self.ranges[row];
+            let mut values: Vec<f64> = self.constraints.iter().map(|column| column[row]).collect();
+            values.push(b);
+            values.extend(range);
+            let scale = common_scale(&values).map_err(|i| {
+                LpErrors::NotIntegral(match i {
+                    i if i < self.coefficients.len() => {
+                        format!("coefficient of {} in row {row}", name(i))
+                    }
+                    i if i == self.coefficients.len() => format!("right-hand side of row {row}"),
+                    _ => format!("range of row {row}"),
+                })
+            })?;
+            if !values.iter().all(|&value| fits(value * scale as f64)) {
+                return Err(LpErrors::IntegerOverflow(format!("row {row}")));
+            }
+            let values = values
+                .iter()
+                .map(|&value| scaled(value, scale))
+                .collect::<Option<Vec<i128>>>()
+                .ok_or_else(|| LpErrors::NotIntegral(format!("row {row}")))?;
+            // the accumulator of the row starts at -rhs and never goes further than the
+            // sum of its coefficients from there
+            let reach = values
+                .iter()
+                .try_fold(0i128, |sum, value| sum.checked_add(value.checked_abs()?));
+            match reach {
+                Some(reach) if reach <= max => {}
+                _ => return Err(LpErrors::IntegerOverflow(format!("row {row}"))),
+            }
+            for (column, &value) in values[..self.coefficients.len()].iter().enumerate() {
+                constraints[column][row] = convert(value);
+            }
+            rhs.push(convert(values[self.coefficients.len()]));
+            ranges.push(range.map(|_| convert(values[self.coefficients.len() + 1])));
+        }
+
+        let mut balas = Balas::from_columns(
+            coefficients.into_iter().map(convert).collect(),
+            constraints,
+            rhs,
+            self.vars.clone(),
+            self.order.clone(),
+        );
+        balas.ranges = ranges;
+        balas.removed = self.removed.clone();
+        balas.transform = Transform {
+            complemented: self.transform.complemented.clone(),
+            negated: self.transform.negated,
+            offset: convert(offset),
+        };
+        Ok(balas)
+    }
+}
+
+impl<I> Balas<I>
+where
+    I: Bounded
+        + Neg<Output = I>
+        + Copy
+        + Display
+        + num::Zero
+        + num::ToPrimitive
+        + FromPrimitive
+        + for<'a> std::ops::AddAssign<&'a I>
+        + for<'a> std::ops::SubAssign<&'a I>
+        + std::cmp::PartialOrd
+        + std::fmt::Debug,
+    Vec<I>: FromIterator<<I as Neg>::Output>,
+{
+    /// Read an LP file into a solver with exact integer arithmetic (see `to_integer`)
+    pub fn from_lp_integer(lp_path: &Path) -> Result<Balas<I>, LpErrors> {
+        Balas::from_lp(lp_path)?.to_integer()
+    }
+
+    /// Read a free MPS file into a solver with exact integer arithmetic (see
+    /// `to_integer`)
+    pub fn from_mps_integer(mps_path: &Path) -> Result<Balas<I>, LpErrors> {
+        Balas::from_mps(mps_path)?.to_integer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Balas, LpErrors};
+    use std::path::Path;
+
+    fn model(costs: &[f64], columns: &[Vec<f64>], b: &[f64]) -> Balas<f64> {
+        let vars = (0..costs.len()).map(|i| format!("x{i}")).collect();
+        Balas::new(costs, &columns.to_vec(), b, &vars)
+    }
+
+    #[test]
+    fn keeps_the_optimal_objective() {
+        for sample in ["demo", "parallel", "v006c032", "v032c032"] {
+            let path = format!("samples/{sample}.lp");
+            let expected = Balas::from_lp(Path::new(&path)).unwrap().solve();
+            let mut balas: Balas<i64> = Balas::from_lp_integer(Path::new(&path)).unwrap();
+            let result = balas.solve();
+            assert_eq!(
+                result.solution.unwrap().objective as f64,
+                expected.solution.unwrap().objective,
+                "{sample}"
+            );
+        }
+    }
+
+    #[test]
+    fn scales_fractional_rows() {
+        // 0.1 x0 + 0.35 x1 >= 0.4, written with a little rounding error
+        let balas = model(&[2.0, 3.0], &[vec![0.1000000000001], vec![0.35]], &[0.4]);
+        let mut integer: Balas<i32> = balas.to_integer().unwrap();
+        assert_eq!(integer.rhs, vec![8]);
+        assert_eq!(integer.solve().solution.unwrap().objective, 5);
+    }
+
+    #[test]
+    fn rejects_fractional_costs() {
+        let balas = model(&[1.5, 1.0], &[vec![1.0], vec![1.0]], &[1.0]);
+        assert!(matches!(
+            balas.to_integer::<i64>(),
+            Err(LpErrors::NotIntegral(what)) if what == "objective coefficient of x0"
+        ));
+    }
+
+    #[test]
+    fn reports_overflow() {
+        // too big for an i128
+        let balas = model(&[1.0, 1.0], &[vec![1e39], vec![1.0]], &[1.0]);
+        assert!(matches!(
+            balas.to_integer::<i64>(),
+            Err(LpErrors::IntegerOverflow(what)) if what == "row 0"
+        ));
+
+        // each coefficient fits in an i32, but their sum does not
+        let balas = model(&[1.0, 1.0], &[vec![2e9], vec![2e9]], &[1.0]);
+        assert!(matches!(
+            balas.to_integer::<i32>(),
+            Err(LpErrors::IntegerOverflow(what)) if what == "row 0"
+        ));
+        assert!(balas.to_integer::<i64>().is_ok());
+
+        let balas = model(&[3e9, 1.0], &[vec![1.0], vec![1.0]], &[1.0]);
+        assert!(matches!(
+            balas.to_integer::<i32>(),
+            Err(LpErrors::IntegerOverflow(what)) if what == "the objective"
+        ));
+    }
+}
@@ -1,10 +1,58 @@
+mod builder;
+mod dot_writer;
+mod extras;
+mod integer;
+mod limits;
 mod lp_errors;
 mod lp_reader;
+mod mps_reader;
+mod must_set;
+mod observer;
+mod ordering;
+mod parallel;
+mod pool;
+mod presolve;
+mod recording;
 mod recursive_solver;
+mod relaxation;
+mod replay;
+mod sol_reader;
+mod sol_writer;
+mod solution;
+mod stats;
+mod surrogate;
+mod verify;
+mod warm_start;
 
+pub use builder::ModelBuilder;
+pub use dot_writer::write_dot;
+pub use limits::{Limit, SolverLimits};
+pub use lp_errors::LpErrors;
+pub use lp_parser_rs::model::sense::{Cmp, Sense};
+pub use must_set::MustSetStats;
+pub use observer::{Control, NoObserver, SolveObserver};
+pub use ordering::BranchOrder;
+pub use presolve::PresolveStats;
+pub use recording::{RecordingLevel, SearchRecording};
+pub use replay::{replay, Replay};
+pub use sol_reader::{read_assignment, read_values, SolutionValues};
+pub use sol_writer::{write_json, write_sol, write_solution, SolutionFormat};
+pub use solution::{Solution, SolveResult, Status, Transform};
+pub use stats::{Improvement, SearchStats};
+pub use verify::{ConstraintCheck, Model, Verification};
+
+use must_set::MustSet;
 use num::Bounded;
+use parallel::SharedLink;
+use pool::SolutionPool;
+use relaxation::Relaxation;
 use serde::{Deserialize, Serialize};
-use std::{fmt::Display, ops::Neg};
+use std::{
+    fmt::Display,
+    ops::Neg,
+    time::{Duration, Instant},
+};
+use surrogate::Surrogate;
 
 type Array<T> = Vec<Vec<T>>;
 
@@ -13,39 +61,162 @@ pub struct Balas<T> {
     pub coefficients: Vec<T>,
     pub constraints: Array<T>,
     pub rhs: Vec<T>,
+    /// How far each row may go above its right-hand side: `Some(0)` for an equality,
+    /// `None` for a plain `>=` row
+    #[serde(default)]
+    pub ranges: Vec<Option<T>>,
     cumulative: Array<T>,
+    /// Like `cumulative`, but the sums of the negative coefficients, which bound how far
+    /// the rows with a range can still come down
+    #[serde(skip_serializing, skip_deserializing)]
+    negative_cumulative: Array<T>,
+    /// The width of every row (the largest value for a row without a range), taken from
+    /// `ranges` when a solve starts.  Empty when no row has a range, which saves the
+    /// search from checking the rows from above.
+    #[serde(skip_serializing, skip_deserializing)]
+    widths: Vec<T>,
     pub best: T,
     #[serde(skip_serializing, skip_deserializing)]
     pub solution: Vec<u8>,
     pub count: usize,
+    /// The statistics of the last search
+    #[serde(default)]
+    pub stats: SearchStats,
+    /// When the last search started
+    #[serde(skip_serializing, skip_deserializing)]
+    started: Option<Instant>,
+    status: Status,
+    elapsed: Duration,
     vars: Vec<String>,
+    /// The caller's index of the variable in each column.  The columns are sorted by
+    /// cost unless another `BranchOrder` is chosen, but callers can give them in any
+    /// order.
+    order: Vec<usize>,
+    /// The caller's index and value of each variable that `presolve` fixed, and so
+    /// took out of the columns
+    #[serde(default)]
+    removed: Vec<(usize, u8)>,
+    transform: Transform<T>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub limits: SolverLimits<T>,
+    #[serde(skip_serializing, skip_deserializing)]
+    deadline: Option<Instant>,
+    #[serde(skip_serializing, skip_deserializing)]
+    next_progress: usize,
+    #[serde(skip_serializing, skip_deserializing)]
+    pool: Option<SolutionPool<T>>,
+    /// Fix variables with the must-set rule at each node (iterative solver only)
+    #[serde(skip_serializing, skip_deserializing)]
+    pub must_set: bool,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub must_set_stats: MustSetStats,
+    /// Prune with a surrogate constraint at each node (iterative solver only)
+    #[serde(skip_serializing, skip_deserializing)]
+    pub surrogate: bool,
+    /// Nodes pruned by the surrogate constraint in the last solve
+    #[serde(skip_serializing, skip_deserializing)]
+    pub surrogate_pruned: usize,
+    /// Prune with a bound from the continuous relaxation at each node (iterative
+    /// solver only)
+    #[serde(skip_serializing, skip_deserializing)]
+    pub relaxation: bool,
+    /// Nodes pruned by the relaxation bound in the last solve
+    #[serde(skip_serializing, skip_deserializing)]
+    pub relaxation_pruned: usize,
+    /// The relaxation bound at the root, for the solver's objective
+    #[serde(skip_serializing, skip_deserializing)]
+    root_bound: Option<f64>,
+    /// The incumbent shared with the other workers of a parallel solve
+    #[serde(skip_serializing, skip_deserializing)]
+    shared: Option<SharedLink<T>>,
+    /// How much of the search to keep in `recording`.  The subproblems of a parallel
+    /// solve are not recorded.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub recording_level: RecordingLevel,
     pub recording: Vec<Record>,
 }
 
 impl<T> Balas<T>
 where
     T: Bounded
-        + Neg
+        + Neg<Output = T>
         + Copy
         + Display
         + num::Zero
+        + num::ToPrimitive
         + for<'a> std::ops::AddAssign<&'a T>
         + for<'a> std::ops::SubAssign<&'a T>
         + std::cmp::PartialOrd
         + std::fmt::Debug,
     Vec<T>: FromIterator<<T as Neg>::Output>,
 {
+    /// Create a solver for: minimize `coeff`·x subject to `constraints`·x >= `b`, where
+    /// `constraints[i]` is the column of coefficients of variable `i` and the costs are
+    /// nonnegative.  The variables can be in any order; solutions are reported in the
+    /// order given here.  Rows that also have an upper limit (such as equalities) are
+    /// given by setting `ranges` afterwards.
     pub fn new(coeff: &[T], constraints: &Array<T>, b: &[T], vars: &Vec<String>) -> Balas<T> {
-        let cumulative = Self::make_cumulative(constraints);
+        // stable, so that variables with equal costs stay in the order given
+        let mut order: Vec<usize> = (0..coeff.len()).collect();
+        order.sort_by(|&a, &b| {
+            coeff[a]
+                .partial_cmp(&coeff[b])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Self::from_columns(
+            order.iter().map(|&i| coeff[i]).collect(),
+            order.iter().map(|&i| constraints[i].clone()).collect(),
+            b.to_vec(),
+            vars.to_owned(),
+            order,
+        )
+    }
+
+    /// Create a solver from columns that are already in the order to branch on, where
+    /// `order` gives the caller's index of the variable in each column
+    fn from_columns(
+        coefficients: Vec<T>,
+        constraints: Array<T>,
+        rhs: Vec<T>,
+        vars: Vec<String>,
+        order: Vec<usize>,
+    ) -> Balas<T> {
+        let cumulative = Self::make_cumulative(&constraints);
+        let negative_cumulative = Self::make_negative_cumulative(&constraints);
+        let num_vars = coefficients.len();
+        let num_rows = rhs.len();
         Balas {
-            coefficients: coeff.to_vec(),
-            constraints: constraints.clone(),
-            rhs: b.to_vec(),
+            coefficients,
+            constraints,
+            rhs,
+            ranges: vec![None; num_rows],
             cumulative,
+            negative_cumulative,
+            widths: vec![],
             best: T::max_value(),
             solution: Vec::new(),
             count: 0,
-            vars: vars.to_owned(),
+            stats: SearchStats::default(),
+            started: None,
+            status: Status::Unsolved,
+            elapsed: Duration::ZERO,
+            vars,
+            order,
+            removed: vec![],
+            transform: Transform::identity(num_vars),
+            limits: SolverLimits::default(),
+            deadline: None,
+            next_progress: 0,
+            pool: None,
+            must_set: false,
+            must_set_stats: MustSetStats::default(),
+            surrogate: false,
+            surrogate_pruned: 0,
+            relaxation: false,
+            relaxation_pruned: 0,
+            root_bound: None,
+            shared: None,
+            recording_level: RecordingLevel::Off,
             recording: vec![],
         }
     }
@@ -53,9 +224,26 @@ where
         self.count = 0;
         self.best = T::max_value();
         self.solution = Vec::new();
+        self.status = Status::Unsolved;
+        self.elapsed = Duration::ZERO;
+        self.must_set_stats = MustSetStats::default();
+        self.surrogate_pruned = 0;
+        self.relaxation_pruned = 0;
+        self.root_bound = None;
+        self.stats = SearchStats::default();
+        self.recording.clear();
+    }
+
+    pub fn solve(&mut self) -> SolveResult<T> {
+        self.solve_with_observer(&mut NoObserver)
     }
 
-    pub fn solve(&mut self) {
+    /// Solve, reporting progress and new incumbents to the `observer`
+    pub fn solve_with_observer(&mut self, observer: &mut dyn SolveObserver<T>) -> SolveResult<T> {
+        let start = Instant::now();
+        self.start_limits(start);
+        self.next_progress = observer.progress_interval();
+        self.prepare_rows();
         let num_vars = self.coefficients.len();
         let mut vars: Vec<u8> = vec![0; num_vars];
         let mut branch = 0u8;
@@ -67,35 +255,83 @@ where
         // right-hand side of the constraints).  This way, we can just compare against 0
         // later on.
         let mut accumulator: Vec<T> = self.rhs.iter().map(|&b| -b).collect();
+        let mut must_set = self.must_set.then(|| MustSet::new(&self.constraints));
+        let mut surrogate = self.surrogate.then(Surrogate::new);
+        let mut relaxation = self.relaxation.then(|| match &self.shared {
+            Some(link) => Relaxation::seeded(link.multipliers()),
+            None => Relaxation::new(self.rhs.len()),
+        });
+        // the workers of a parallel solve start from the bound of the whole problem
+        // instead of finding their own
+        if let (Some(relaxation), None) = (&mut relaxation, &self.shared) {
+            let bound =
+                relaxation.root_bound(&self.constraints, &self.coefficients, &accumulator, None);
+            self.root_bound = Some(bound);
+        }
+
+        // The search below never visits the all-zeros assignment, so check it first.
+        // Since no objective coefficient is negative, nothing can do better.
+        if self.zeros_feasible(&accumulator) {
+            self.solution = vars.clone();
+            // nothing can beat this, so the search is over whatever the observer says
+            // (unless more solutions are wanted for the pool)
+            self.new_incumbent(observer);
+            if let Some(pool) = &mut self.pool {
+                pool.insert(T::zero(), &vars);
+            } else {
+                return self.finish(start, None, observer);
+            }
+        }
 
+        let mut stopped = None;
         loop {
             // std::thread::sleep(std::time::Duration::from_secs_f32(0.5));
             // println!("Nun vars: {num_vars}, index: {index}, branch: {branch}, state: {state:?}");
-            // Alias the current column of the constraints and grab the coefficients value
-            let cons = &self.constraints[index];
-            let coeff = &self.coefficients[index];
-
             match state {
                 Flow::Terminate => break,
                 Flow::Backtrack => {
+                    let fixed = match &mut must_set {
+                        Some(must_set) => {
+                            must_set.undo(index);
+                            must_set.fixed[index]
+                        }
+                        None => None,
+                    };
                     if vars[index] == 1 {
                         if index == 0 {
                             state = Flow::Terminate;
                         } else {
                             // we have to reverse what we did before we leave
+                            let cons = &self.constraints[index];
                             accumulator.iter_mut().zip(cons).for_each(|(a, b)| *a -= b);
-                            objective -= coeff;
+                            objective -= &self.coefficients[index];
                             vars[index] = 0;
                             index -= 1;
+                            self.stats.backtracks += 1;
                         }
+                    } else if fixed == Some(0) {
+                        // the ones branch is known to be infeasible
+                        index -= 1;
+                        self.stats.backtracks += 1;
                     } else {
                         state = Flow::Normal;
                         branch = 1;
                     }
                 }
                 Flow::Normal => {
+                    stopped = self
+                        .check_limits()
+                        .or_else(|| self.sync_shared())
+                        .or_else(|| self.progress(index + 1, observer));
+                    if stopped.is_some() {
+                        break;
+                    }
                     self.count += 1;
 
+                    // Alias the current column of the constraints and grab the coefficients value
+                    let cons = &self.constraints[index];
+                    let coeff = &self.coefficients[index];
+
                     if branch == 1 {
                         vars[index] = branch;
                         // Update the accumulator.  This only needs to be done in the ones branch
@@ -106,73 +342,352 @@ where
 
                         // If we're already not better than the current best objective, then
                         // we can prune this entire branch.
-                        if objective >= self.best {
+                        if self.prunes(objective) {
+                            self.record(
+                                &vars[..=index],
+                                objective,
+                                &accumulator,
+                                NodeState::Suboptimal,
+                            );
                             state = Flow::Backtrack;
                             continue;
                         } else {
                             // Check if constraints satisfied, while updating the accumulator.
                             // We do not have to check the 0 branch, as the accumulator is not changed there.
                             // If all of constraints are satisfied, then we are fathomed and we can't do any better.
-                            if accumulator.iter().all(|x| *x >= T::zero()) {
-                                self.best = objective;
-                                // println!("{objective} {:?}", &vars[..=index]);
-                                self.solution = vars.clone();
-                                state = Flow::Backtrack;
-                                continue;
+                            if Self::satisfied(&accumulator, &self.widths) {
+                                if let Some(pool) = &mut self.pool {
+                                    pool.insert(objective, &vars);
+                                }
+                                self.record(
+                                    &vars[..=index],
+                                    objective,
+                                    &accumulator,
+                                    NodeState::Fathomed,
+                                );
+                                if objective < self.best {
+                                    self.best = objective;
+                                    // println!("{objective} {:?}", &vars[..=index]);
+                                    self.solution = vars.clone();
+                                    stopped = self.new_incumbent(observer);
+                                    if stopped.is_some() {
+                                        break;
+                                    }
+                                }
+                                // A pool may still want the descendants, which are worse but
+                                // also feasible
+                                if self.pool.is_none() {
+                                    state = Flow::Backtrack;
+                                    continue;
+                                }
                             }
                         }
                     }
 
                     // This is the same behavior for either a 0 or 1 branch
                     // If there is a potentially feasible descendant, then keep descending the tree
-                    if let Some(ccons) = self.cumulative.get(index) {
-                        if accumulator
-                            .iter()
-                            .zip(ccons)
-                            .all(|(&a, &b)| a + b >= T::zero())
-                        {
-                            index += 1;
-                            branch = 0;
+                    if index < self.cumulative.len() {
+                        if Self::completable(
+                            &accumulator,
+                            &self.cumulative[index],
+                            &self.negative_cumulative[index],
+                            &self.widths,
+                        ) {
+                            if let Some(surrogate) = &mut surrogate {
+                                // the pool has its own idea of what is good enough
+                                let gap = self.pool.is_none().then(|| {
+                                    let mut gap = self.best;
+                                    gap -= &objective;
+                                    gap.to_f64().unwrap_or(f64::INFINITY)
+                                });
+                                if surrogate.prunes(
+                                    index,
+                                    &self.constraints,
+                                    &self.coefficients,
+                                    &accumulator,
+                                    gap,
+                                ) {
+                                    self.record(
+                                        &vars[..=index],
+                                        objective,
+                                        &accumulator,
+                                        NodeState::Skipped,
+                                    );
+                                    state = Flow::Backtrack;
+                                    continue;
+                                }
+                            }
+                            if let Some(relaxation) = &mut relaxation {
+                                // only worth it once there is something to beat
+                                if self.pool.is_none() && self.best < T::max_value() {
+                                    let mut gap = self.best;
+                                    gap -= &objective;
+                                    if relaxation.prunes(
+                                        index,
+                                        &self.constraints,
+                                        &self.coefficients,
+                                        &accumulator,
+                                        gap.to_f64().unwrap_or(f64::INFINITY),
+                                    ) {
+                                        self.record(
+                                            &vars[..=index],
+                                            objective,
+                                            &accumulator,
+                                            NodeState::Skipped,
+                                        );
+                                        state = Flow::Backtrack;
+                                        continue;
+                                    }
+                                }
+                            }
+                            if let Some(must_set) = &mut must_set {
+                                let feasible = must_set.propagate(
+                                    index,
+                                    &self.constraints,
+                                    &self.coefficients,
+                                    &accumulator,
+                                    &self.cumulative[index],
+                                    objective,
+                                    |bound| self.prunes(bound),
+                                );
+                                if !feasible {
+                                    self.record(
+                                        &vars[..=index],
+                                        objective,
+                                        &accumulator,
+                                        NodeState::Skipped,
+                                    );
+                                    state = Flow::Backtrack;
+                                    continue;
+                                }
+                                self.record(
+                                    &vars[..=index],
+                                    objective,
+                                    &accumulator,
+                                    NodeState::Visited,
+                                );
+                                index += 1;
+                                // a variable that is forced to 1 skips its zeros branch
+                                branch = must_set.fixed[index].unwrap_or(0);
+                            } else {
+                                self.record(
+                                    &vars[..=index],
+                                    objective,
+                                    &accumulator,
+                                    NodeState::Visited,
+                                );
+                                index += 1;
+                                branch = 0;
+                            }
                         } else {
+                            // a feasible node can always be completed with zeros
+                            self.record(
+                                &vars[..=index],
+                                objective,
+                                &accumulator,
+                                NodeState::ImpossibleChildren,
+                            );
                             state = Flow::Backtrack;
                         }
                     } else {
+                        self.record(
+                            &vars[..=index],
+                            objective,
+                            &accumulator,
+                            NodeState::Infeasible,
+                        );
                         state = Flow::Backtrack;
                     }
                 }
             }
         }
+        if let Some(must_set) = must_set {
+            self.must_set_stats = must_set.stats;
+        }
+        if let Some(surrogate) = surrogate {
+            self.surrogate_pruned = surrogate.pruned;
+        }
+        if let Some(mut relaxation) = relaxation {
+            self.relaxation_pruned = relaxation.pruned;
+            // with an incumbent to aim for, the root bound can usually be improved
+            if !self.solution.is_empty() && self.shared.is_none() {
+                let accumulator: Vec<T> = self.rhs.iter().map(|&b| -b).collect();
+                let incumbent = self.best.to_f64();
+                let bound = relaxation.root_bound(
+                    &self.constraints,
+                    &self.coefficients,
+                    &accumulator,
+                    incumbent,
+                );
+                self.root_bound = self.root_bound.map(|root| root.max(bound));
+            }
+        }
+        self.finish(start, stopped, observer)
     }
 
-    fn record(&mut self, label: &str, state: NodeState) {
-        self.recording.push(Record {
-            node: label.to_string(),
-            state,
-        });
+    /// Whether a node with this objective (or any of its descendants) can be skipped
+    fn prunes(&self, objective: T) -> bool {
+        match &self.pool {
+            None => objective >= self.best,
+            Some(pool) => pool.prunes(objective),
+        }
     }
 
-    pub fn report(&self) {
-        if self.best != T::max_value() {
-            println!("Optimal value: {}", self.best);
-            println!("Solution:");
-            for (var, value) in self.vars.iter().zip(self.solution.iter()) {
-                println!("  {var}: {}", value);
-            }
+    /// Fill in the row data that the search needs but that is not serialized
+    fn prepare_rows(&mut self) {
+        self.widths = self.row_widths();
+        if self.negative_cumulative.len() != self.cumulative.len() {
+            self.negative_cumulative = Self::make_negative_cumulative(&self.constraints);
+        }
+    }
+
+    /// The widths of the rows for `satisfied` and `completable` (see `widths`)
+    fn row_widths(&self) -> Vec<T> {
+        if self.ranges.iter().all(Option::is_none) {
+            return vec![];
+        }
+        self.ranges
+            .iter()
+            .map(|width| width.unwrap_or(T::max_value()))
+            .collect()
+    }
+
+    /// Whether the accumulator (left-hand sides minus right-hand sides) satisfies every
+    /// row, given the `widths` of the rows
+    fn satisfied(accumulator: &[T], widths: &[T]) -> bool {
+        if widths.is_empty() {
+            accumulator.iter().all(|a| *a >= T::zero())
+        } else {
+            accumulator
+                .iter()
+                .zip(widths)
+                .all(|(a, width)| *a >= T::zero() && a <= width)
+        }
+    }
+
+    /// Whether some completion could still satisfy every row, given the sums of the
+    /// `positive` and `negative` coefficients of the columns that are left
+    fn completable(accumulator: &[T], positive: &[T], negative: &[T], widths: &[T]) -> bool {
+        if widths.is_empty() {
+            accumulator
+                .iter()
+                .zip(positive)
+                .all(|(&a, &b)| a + b >= T::zero())
+        } else {
+            accumulator
+                .iter()
+                .zip(positive)
+                .zip(negative)
+                .zip(widths)
+                .all(|(((&a, &b), &c), width)| a + b >= T::zero() && a + c <= *width)
+        }
+    }
+
+    /// Check (and record) the all-zeros assignment, given the initial accumulator
+    fn zeros_feasible(&mut self, accumulator: &[T]) -> bool {
+        if T::zero() < self.best && Self::satisfied(accumulator, &self.widths) {
+            self.record(&[], T::zero(), accumulator, NodeState::Fathomed);
+            self.best = T::zero();
+            true
         } else {
-            println!("No solution");
+            self.record(&[], T::zero(), accumulator, NodeState::Visited);
+            false
         }
+    }
+
+    /// Record the outcome of a search, which is complete unless it was `stopped`
+    fn finish(
+        &mut self,
+        start: Instant,
+        stopped: Option<Status>,
+        observer: &mut dyn SolveObserver<T>,
+    ) -> SolveResult<T> {
+        self.elapsed = start.elapsed();
+        self.status = match stopped {
+            Some(status) => status,
+            None if self.solution.is_empty() => Status::Infeasible,
+            None => Status::Optimal,
+        };
+        let result = self.result();
+        observer.on_finish(&result);
+        result
+    }
 
-        println!("Examined {:?} nodes", self.count);
+    /// The outcome of the last solve
+    pub fn result(&self) -> SolveResult<T> {
+        SolveResult {
+            status: self.status.clone(),
+            solution: self.best_solution(),
+            nodes: self.count,
+            elapsed: self.elapsed,
+            stats: self.stats.clone(),
+            bound: self.root_bound.map(|bound| {
+                let bound = bound + self.transform.offset.to_f64().unwrap_or(0.0);
+                if self.transform.negated {
+                    0.0 - bound
+                } else {
+                    bound
+                }
+            }),
+        }
+    }
+
+    /// How the problem was rewritten for the solver
+    pub fn transform(&self) -> &Transform<T> {
+        &self.transform
+    }
+
+    /// The best solution found, mapped back to the variables and objective of the
+    /// original problem.
+    pub fn best_solution(&self) -> Option<Solution<T>> {
+        if self.solution.is_empty() {
+            return None;
+        }
+        Some(self.to_solution(self.best, &self.solution))
+    }
+
+    /// Map an objective value and column values of the solver back to the original problem
+    fn to_solution(&self, objective: T, columns: &[u8]) -> Solution<T> {
+        let mut values = vec![0; self.vars.len()];
+        for (&original, &value) in self.order.iter().zip(columns) {
+            values[original] = self.transform.value(original, value);
+        }
+        for &(original, value) in &self.removed {
+            values[original] = self.transform.value(original, value);
+        }
+        let variables = self.vars.iter().cloned().zip(values).collect();
+        Solution {
+            objective: self.transform.objective(objective),
+            variables,
+        }
+    }
+
+    pub fn report(&self) {
+        println!("{}", self.result());
     }
 
     pub fn make_cumulative(constraints: &Array<T>) -> Array<T> {
+        Self::cumulative_sums(constraints, |val| *val > T::zero())
+    }
+
+    fn make_negative_cumulative(constraints: &Array<T>) -> Array<T> {
+        Self::cumulative_sums(constraints, |val| *val < T::zero())
+    }
+
+    /// Recompute both kinds of cumulative sums after the columns have changed
+    pub(crate) fn update_cumulative(&mut self) {
+        self.cumulative = Self::make_cumulative(&self.constraints);
+        self.negative_cumulative = Self::make_negative_cumulative(&self.constraints);
+    }
+
+    fn cumulative_sums(constraints: &Array<T>, include: impl Fn(&T) -> bool) -> Array<T> {
         let num_cols = constraints[0].len();
         let mut running_total = vec![T::zero(); num_cols];
         let mut cumulative = vec![];
 
         for row in constraints.iter().skip(1).rev() {
             for (i, val) in row.iter().enumerate() {
-                if *val > T::zero() {
+                if include(val) {
                     running_total[i] += val;
                 }
             }
@@ -190,20 +705,68 @@ enum Flow {
     Normal,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// What happened at a node of the search
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 pub enum NodeState {
     Default,
     Active,
+    /// Its children were searched
     Visited,
+    /// It satisfies every constraint, so none of its descendants can do better
     Fathomed,
+    /// It is a leaf that does not satisfy the constraints
     Infeasible,
+    /// Its objective is already no better than the incumbent
     Suboptimal,
+    /// No completion of it can satisfy the constraints
     ImpossibleChildren,
+    /// Pruned by the must-set rule, the surrogate constraint or the relaxation bound
     Skipped,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+/// A node of the search, as recorded when `recording_level` is not `Off`.  The records
+/// are in the order the nodes were examined.
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Record {
+    /// The path to the node: the value of each column from the first, so the length is
+    /// the depth and the last character is the branch taken
     pub node: String,
     pub state: NodeState,
+    /// Whether the node became the new incumbent
+    #[serde(default)]
+    pub incumbent: bool,
+    /// The solver's objective at the node
+    #[serde(default)]
+    pub objective: f64,
+    /// How many constraints are violated at the node
+    #[serde(default)]
+    pub violated: usize,
+    /// The total amount by which they are violated
+    #[serde(default)]
+    pub violation: f64,
+    /// The left-hand side minus the right-hand side of each constraint (only with
+    /// `RecordingLevel::Full`)
+    #[serde(default)]
+    pub accumulator: Vec<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_after_a_serde_round_trip() {
+        let vars = vec!["x".to_string(), "y".to_string(), "z".to_string()];
+        let constraints = vec![vec![1.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+        let mut balas = Balas::new(&[3.0, 2.0, 4.0], &constraints, &[2.0, 1.0], &vars);
+        balas.ranges[0] = Some(0.0);
+        let expected = balas.solve().solution.unwrap().objective;
+
+        let json = serde_json::to_string(&balas).unwrap();
+        let mut loaded: Balas<f64> = serde_json::from_str(&json).unwrap();
+        loaded.reset();
+        let result = loaded.solve();
+        assert_eq!(result.status, Status::Optimal);
+        assert_eq!(result.solution.unwrap().objective, expected);
+    }
 }
@@ -0,0 +1,96 @@
+use crate::{Balas, Status, Transform};
+use serde::{Deserialize, Serialize};
+use std::ops::{AddAssign, SubAssign};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How many nodes are examined between checks of the clock and the cancellation flag
+/// (must be a power of two)
+pub(crate) const CHECK_INTERVAL: usize = 1024;
+
+/// The limit that stopped a search
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum Limit {
+    Time,
+    Nodes,
+    Target,
+}
+
+/// Bounds on a solve.  When one of them is reached, the solver stops and reports the
+/// best solution found so far.
+#[derive(Clone, Debug)]
+pub struct SolverLimits<T> {
+    /// Wall-clock time allowed for each solve
+    pub time_limit: Option<Duration>,
+    /// Maximum number of nodes to examine
+    pub node_limit: Option<usize>,
+    /// Stop as soon as a solution this good is found.  This is a value of the
+    /// original objective, so "good" follows the original sense.
+    pub target: Option<T>,
+    /// Stops the solve when set, e.g. from another thread
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl<T> Default for SolverLimits<T> {
+    fn default() -> Self {
+        SolverLimits {
+            time_limit: None,
+            node_limit: None,
+            target: None,
+            cancel: None,
+        }
+    }
+}
+
+impl<T> SolverLimits<T>
+where
+    T: Copy + PartialOrd + num::Zero + for<'a> AddAssign<&'a T> + for<'a> SubAssign<&'a T>,
+{
+    /// Whether the solver's objective value `best` meets the target
+    pub(crate) fn target_reached(&self, transform: &Transform<T>, best: T) -> bool {
+        let Some(target) = self.target else {
+            return false;
+        };
+        let value = transform.objective(best);
+        if transform.negated {
+            value >= target
+        } else {
+            value <= target
+        }
+    }
+}
+
+impl<T> Balas<T> {
+    /// Start the clock for the time limit and the statistics
+    pub(crate) fn start_limits(&mut self, start: Instant) {
+        self.started = Some(start);
+        self.deadline = self.limits.time_limit.map(|limit| start + limit);
+    }
+
+    /// Check the node limit, and periodically the time limit and the cancellation flag.
+    /// This is called before each node is examined.
+    pub(crate) fn check_limits(&self) -> Option<Status> {
+        if self
+            .limits
+            .node_limit
+            .is_some_and(|limit| self.count >= limit)
+        {
+            return Some(Status::LimitReached(Limit::Nodes));
+        }
+        if self.count & (CHECK_INTERVAL - 1) == 0 {
+            if let Some(cancel) = &self.limits.cancel {
+                if cancel.load(Ordering::Relaxed) {
+                    return Some(Status::Interrupted);
+                }
+            }
+            if self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                return Some(Status::LimitReached(Limit::Time));
+            }
+        }
+        None
+    }
+}
@@ -14,9 +14,42 @@ pub enum LpErrors {
     #[error("Can only handle Standard constraints")]
     UnexpectedConstraintType,
 
-    #[error("failed to read the LP file")]
+    #[error("failed to read the input file")]
     FileReadError(#[source] std::io::Error),
 
+    #[error("failed to write the output file")]
+    FileWriteError(#[source] std::io::Error),
+
     #[error("failed to parse the LP file")]
     LPParseError(#[source] anyhow::Error),
+
+    #[error("failed to parse the MPS file at line {line}: {reason}")]
+    MPSParseError { line: usize, reason: String },
+
+    #[error("failed to parse the solution file at line {line}: {reason}")]
+    SolutionParseError { line: usize, reason: String },
+
+    #[error("failed to parse the recording file")]
+    RecordingParseError(#[source] serde_json::Error),
+
+    #[error("Unknown variable \"{0}\"")]
+    UnknownVariable(String),
+
+    #[error("The name \"{0}\" is used more than once")]
+    DuplicateName(String),
+
+    #[error("No value given for variable \"{0}\"")]
+    MissingValue(String),
+
+    #[error("The bounds of \"{0}\" allow neither 0 nor 1")]
+    InfeasibleBound(String),
+
+    #[error("The {0} cannot be made a whole number")]
+    NotIntegral(String),
+
+    #[error("The values of {0} are too large for the integer type")]
+    IntegerOverflow(String),
+
+    #[error("The starting solution violates the constraints")]
+    InfeasibleStart,
 }
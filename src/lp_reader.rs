@@ -1,5 +1,6 @@
+use crate::extras::{split_lp_extras, Extras};
 use crate::lp_errors::LpErrors;
-use crate::Balas;
+use crate::{Balas, Transform};
 use lp_parser_rs::model::coefficient::Coefficient;
 use lp_parser_rs::model::constraint::Constraint;
 use lp_parser_rs::model::lp_problem::LPProblem;
@@ -8,7 +9,7 @@ use lp_parser_rs::model::sense::{Cmp, Sense};
 // use lp_parser_rs::model::variable::VariableType;
 use lp_parser_rs::model::variable::Variable;
 use lp_parser_rs::parse::parse_lp_file;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
@@ -17,9 +18,30 @@ type Constraints = HashMap<String, Constraint>;
 impl Balas<f64> {
     pub fn from_lp(lp_path: &Path) -> Result<Balas<f64>, LpErrors> {
         let code = fs::read_to_string(lp_path).map_err(LpErrors::FileReadError)?;
+        let (code, extras) = split_lp_extras(&code)?;
         let lp = parse_lp_file(&code).map_err(LpErrors::LPParseError)?;
+        Self::from_lp_problem(&lp, &extras)
+    }
 
-        let lp = normalize_for_balas(&lp)?;
+    /// Build a solver from an already parsed problem.  This is shared by all of the
+    /// file readers so that every format goes through the same normalization.  Range
+    /// rows are kept as single rows, and variables fixed by their bounds are taken out
+    /// before the search.
+    pub(crate) fn from_lp_problem(lp: &LPProblem, extras: &Extras) -> Result<Balas<f64>, LpErrors> {
+        // The width of a range does not change with the normalization, which only
+        // shifts both of its sides
+        let mut widths = HashMap::new();
+        for (name, upper) in &extras.ranges {
+            match lp.constraints.get(name) {
+                Some(Constraint::Standard {
+                    sense: Cmp::GreaterOrEqual,
+                    rhs,
+                    ..
+                }) => widths.insert(name, upper - rhs),
+                _ => return Err(LpErrors::UnexpectedConstraintType),
+            };
+        }
+        let (lp, normalization) = normalize_for_balas(lp)?;
 
         // dbg!(&lp);
 
@@ -27,9 +49,28 @@ impl Balas<f64> {
         let vars: Vec<String>;
         let index: std::collections::HashMap<String, usize>;
         if let Some(objective) = lp.objectives.first() {
-            // sort the variables by coefficient
-            let mut obj: Vec<_> = objective.coefficients.iter().collect();
-            obj.sort_by(|a, b| a.coefficient.partial_cmp(&b.coefficient).unwrap());
+            // Variables that only appear in the constraints have a zero cost
+            let mut obj = objective.coefficients.clone();
+            let in_objective: HashSet<&str> = obj.iter().map(|c| c.var_name.as_str()).collect();
+            let constraint_vars = lp
+                .constraints
+                .values()
+                .flat_map(|constraint| match constraint {
+                    Constraint::Standard { coefficients, .. } => coefficients.iter(),
+                    _ => [].iter(),
+                });
+            let mut constraint_only: Vec<&String> = lp
+                .variables
+                .keys()
+                .chain(constraint_vars.map(|c| &c.var_name))
+                .filter(|v| !in_objective.contains(v.as_str()))
+                .collect();
+            constraint_only.sort();
+            constraint_only.dedup();
+            obj.extend(constraint_only.into_iter().map(|v| Coefficient {
+                var_name: v.clone(),
+                coefficient: 0.0,
+            }));
             vars = obj.iter().map(|v| v.var_name.to_owned()).collect();
 
             // Create mapping from variable name to constraints column (visually)
@@ -47,19 +88,25 @@ impl Balas<f64> {
             return Err(LpErrors::NoObjective);
         }
 
-        let num_vars = lp.variables.len();
+        let num_vars = vars.len();
         let num_constraints = lp.constraints.len();
         let mut constraints: Vec<_> = (0..num_vars).map(|_| vec![0.0; num_constraints]).collect();
         let mut rhs = vec![];
+        let mut ranges = vec![];
         for (col, (_, constraint)) in lp.constraints.iter().enumerate() {
             match constraint {
                 Constraint::Standard {
-                    name: _,
+                    name,
                     coefficients,
-                    sense: _,
+                    sense,
                     rhs: this_rhs,
                 } => {
                     rhs.push(*this_rhs);
+                    // an equality is a row that cannot go above its right-hand side
+                    ranges.push(match widths.get(name) {
+                        Some(&width) => Some(width),
+                        None => (*sense == Cmp::Equal).then_some(0.0),
+                    });
                     for coeff in coefficients {
                         if let Some(row) = index.get(&coeff.var_name) {
                             constraints[*row][col] = coeff.coefficient;
@@ -73,26 +120,61 @@ impl Balas<f64> {
         // dbg!(&constraints);
         // dbg!(&rhs);
         // dbg!(&vars);
-        Ok(Balas::new(&coefficients, &constraints, &rhs, &vars))
+        let mut balas = Balas::new(&coefficients, &constraints, &rhs, &vars);
+        balas.ranges = ranges;
+        balas.transform = Transform {
+            complemented: vars
+                .iter()
+                .map(|v| normalization.complemented.contains(v))
+                .collect(),
+            negated: normalization.negated,
+            offset: normalization.offset,
+        };
+        balas.transform.add_constant(extras.objective_constant);
+
+        let mut fixed = vec![];
+        for (var, &value) in &extras.fixed {
+            let Some(original) = vars.iter().position(|v| v == var) else {
+                return Err(LpErrors::UnknownVariable(var.clone()));
+            };
+            fixed.push((original, value));
+        }
+        balas.fix_variables(&fixed);
+        Ok(balas)
     }
 }
 
+/// What `normalize_for_balas` changed, so that solutions can be mapped back
+struct Normalization {
+    negated: bool,
+    complemented: HashSet<String>,
+    offset: f64,
+}
+
 /// The Balas algorithm requires that:
 /// - the problem sense must be "minimize".  A "maximize" sense
 ///     will be converted by negating the objective coefficients.
 /// - all constraints be of the >= sense.  So, this function
 ///     will convert <= sense constraints to >= by negating
 ///     the coefficients and the rhs. Equality constraints
-///     need to have a negated constraint added.
+///     are kept, and the solver checks them from both sides.
 /// - all objective coefficients must be positive.  Negative
 ///     coefficients will be converted by replacing "x"
 ///     with "y = 1 - x"
 ///
-fn normalize_for_balas(lp: &LPProblem) -> Result<LPProblem, LpErrors> {
+fn normalize_for_balas(lp: &LPProblem) -> Result<(LPProblem, Normalization), LpErrors> {
     let problem_name = format!("{}_balas", lp.problem_name);
     let objective = create_min_objective(lp)?;
     let constraints = create_ge_constraints(lp)?;
-    let (objective, constraints) = fix_neg_variables(&objective, &constraints);
+
+    // Complementing x with negative coefficient c leaves the constant c behind
+    let offset = objective
+        .coefficients
+        .iter()
+        .map(|c| c.coefficient)
+        .filter(|&c| c < 0.0)
+        .sum();
+    let (objective, constraints, complemented) = fix_neg_variables(&objective, &constraints);
 
     // copy variables while making sure they all are binary
     let mut variables = HashMap::new();
@@ -103,16 +185,28 @@ fn normalize_for_balas(lp: &LPProblem) -> Result<LPProblem, LpErrors> {
         variables.insert(s.clone(), Variable::Binary);
     }
 
-    Ok(LPProblem {
-        problem_name,
-        problem_sense: Sense::Minimize,
-        variables,
-        objectives: vec![objective],
-        constraints,
-    })
+    let normalization = Normalization {
+        negated: lp.problem_sense == Sense::Maximize,
+        complemented: complemented.into_iter().collect(),
+        offset,
+    };
+
+    Ok((
+        LPProblem {
+            problem_name,
+            problem_sense: Sense::Minimize,
+            variables,
+            objectives: vec![objective],
+            constraints,
+        },
+        normalization,
+    ))
 }
 
-fn fix_neg_variables(objective: &Objective, constraints: &Constraints) -> (Objective, Constraints) {
+fn fix_neg_variables(
+    objective: &Objective,
+    constraints: &Constraints,
+) -> (Objective, Constraints, Vec<String>) {
     let mut to_change = Vec::<&str>::new();
 
     let mut coeff: Vec<Coefficient> = vec![];
@@ -161,76 +255,44 @@ fn fix_neg_variables(objective: &Objective, constraints: &Constraints) -> (Objec
             new_constraints.insert(label.clone(), new_constraint);
         }
     }
-    (objective, new_constraints)
+    let complemented = to_change.iter().map(|v| v.to_string()).collect();
+    (objective, new_constraints, complemented)
 }
 
 fn create_ge_constraints(lp: &LPProblem) -> Result<Constraints, LpErrors> {
-    // make all constraints be of the >= sense
-    let mut additional: Vec<(String, Constraint)> = vec![];
-    let mut constraints: Constraints = lp
-        .constraints
+    // make all constraints be of the >= sense (or =)
+    lp.constraints
         .iter()
-        .map(|(label, constraint)| {
-            match constraint {
-                Constraint::Standard {
-                    name,
-                    coefficients,
-                    sense,
-                    rhs,
-                } => {
-                    let mut my_sense = sense.clone();
-                    let mut my_coefficients = coefficients.to_vec();
-                    let mut my_rhs = *rhs;
-                    match sense {
-                        Cmp::LessOrEqual => {
-                            my_sense = Cmp::GreaterOrEqual;
-                            my_rhs = -rhs;
-                            my_coefficients
-                                .iter_mut()
-                                .for_each(|c| c.coefficient = -c.coefficient);
-                        }
-                        Cmp::Equal => {
-                            // change over "=" to ">="
-                            my_sense = Cmp::GreaterOrEqual;
-
-                            // create negated copy of constraint
-                            let new_coeff: Vec<Coefficient> = my_coefficients
-                                .iter()
-                                .map(|c| Coefficient {
-                                    var_name: c.var_name.clone(),
-                                    coefficient: -c.coefficient,
-                                })
-                                .collect();
-                            additional.push((
-                                format!("{}_balas", label),
-                                Constraint::Standard {
-                                    name: format!("{}_balas", name),
-                                    coefficients: new_coeff,
-                                    sense: Cmp::GreaterOrEqual,
-                                    rhs: -rhs,
-                                },
-                            ));
-                        }
-                        _ => {}
-                    }
-                    (
-                        label.to_owned(),
-                        Constraint::Standard {
-                            name: name.to_owned(),
-                            coefficients: my_coefficients,
-                            sense: my_sense,
-                            rhs: my_rhs,
-                        },
-                    )
+        .map(|(label, constraint)| match constraint {
+            Constraint::Standard {
+                name,
+                coefficients,
+                sense,
+                rhs,
+            } => {
+                let mut my_sense = sense.clone();
+                let mut my_coefficients = coefficients.to_vec();
+                let mut my_rhs = *rhs;
+                if *sense == Cmp::LessOrEqual {
+                    my_sense = Cmp::GreaterOrEqual;
+                    my_rhs = -rhs;
+                    my_coefficients
+                        .iter_mut()
+                        .for_each(|c| c.coefficient = -c.coefficient);
                 }
-                _ => unimplemented!(),
+                Ok((
+                    label.to_owned(),
+                    Constraint::Standard {
+                        name: name.to_owned(),
+                        coefficients: my_coefficients,
+                        sense: my_sense,
+                        rhs: my_rhs,
+                    },
+                ))
             }
+            _ => Err(LpErrors::UnexpectedConstraintType),
         })
-        .collect();
-    for (label, constraint) in additional {
-        constraints.insert(label, constraint);
-    }
-    Ok(constraints)
+        .collect()
 }
 
 fn create_min_objective(lp: &LPProblem) -> Result<Objective, LpErrors> {
@@ -1,17 +1,32 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use argh::FromArgs;
-use balas::Balas;
+use balas::{
+    read_assignment, read_values, replay, write_dot, write_solution, Balas, BranchOrder, Control,
+    LpErrors, Model, NoObserver, PresolveStats, RecordingLevel, SearchRecording, Solution,
+    SolutionFormat, SolveObserver,
+};
+use num::{Bounded, NumCast};
+use serde::Serialize;
+use std::fmt::Display;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Write};
+use std::ops::Neg;
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[derive(FromArgs)]
 /// Solve a Binary-Variable Linear Program
 struct Args {
-    /// input file in LP format
+    /// input file in LP or MPS format
     #[argh(positional)]
-    infile: PathBuf,
+    infile: Option<PathBuf>,
+
+    #[argh(subcommand)]
+    command: Option<Command>,
+
+    /// read an MPS input file using fixed column positions
+    #[argh(switch)]
+    fixed_mps: bool,
 
     /// how many repetitions (for timing)
     #[argh(option, short = 'r', default = "1")]
@@ -21,31 +36,327 @@ struct Args {
     #[argh(option)]
     outfile: Option<PathBuf>,
 
+    /// record the search tree for the --outfile: off (the default), nodes or full (which
+    /// adds every constraint's value at each node)
+    #[argh(option, default = "RecordingLevel::Off")]
+    record: RecordingLevel,
+
+    /// write the solution to this file
+    #[argh(option)]
+    solfile: Option<PathBuf>,
+
+    /// format of the solution file: sol (the default) or json
+    #[argh(option, default = "SolutionFormat::Sol")]
+    format: SolutionFormat,
+
     /// use this heuristic pre-solve
     #[argh(option)]
     heuristic: Option<f64>,
 
+    /// start from the feasible solution in this file (lines of "name value")
+    #[argh(option)]
+    start: Option<PathBuf>,
+
+    /// check the solution in this file against the model instead of solving
+    #[argh(option)]
+    verify: Option<PathBuf>,
+
+    /// check the solver's solution against the original model
+    #[argh(switch)]
+    check: bool,
+
+    /// simplify the problem before solving it
+    #[argh(switch)]
+    presolve: bool,
+
+    /// solve with exact 64-bit integer arithmetic (the objective has to have whole
+    /// coefficients, and the constraints whole or fractional ones)
+    #[argh(switch)]
+    integer: bool,
+
+    /// order to branch on the variables: cost (the default), coverage, ratio or random[:seed]
+    #[argh(option, default = "BranchOrder::Cost")]
+    order: BranchOrder,
+
+    /// branch on the variables by the priorities in this file (lines of "name priority",
+    /// highest first)
+    #[argh(option)]
+    priority: Option<PathBuf>,
+
+    /// fix variables that a constraint forces (Balas' must-set rule)
+    #[argh(switch)]
+    must_set: bool,
+
+    /// prune with a surrogate constraint of the violated constraints
+    #[argh(switch)]
+    surrogate: bool,
+
+    /// prune with a bound from the continuous relaxation, and report the optimality gap
+    #[argh(switch)]
+    relaxation: bool,
+
+    /// solve on this many threads
+    #[argh(option)]
+    threads: Option<usize>,
+
+    /// with --threads, split the search into subproblems by fixing this many variables
+    #[argh(option)]
+    split: Option<usize>,
+
     ///use the original recursive code
     #[argh(switch)]
     recursive: bool,
 
+    /// stop each solve after this many seconds
+    #[argh(option)]
+    time_limit: Option<f64>,
+
+    /// stop each solve after examining this many nodes
+    #[argh(option)]
+    node_limit: Option<usize>,
+
+    /// stop as soon as a solution with this objective value (or better) is found
+    #[argh(option)]
+    target: Option<f64>,
+
+    /// print new incumbents and periodic progress to stderr
+    #[argh(switch)]
+    progress: bool,
+
+    /// keep this many of the best solutions (uses the iterative solver)
+    #[argh(option)]
+    pool: Option<usize>,
+
+    /// only pool solutions within this distance of the optimal objective (0 gives all optimal solutions)
+    #[argh(option)]
+    pool_tolerance: Option<f64>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Command {
+    Dot(DotArgs),
+    Replay(ReplayArgs),
+}
+
+#[derive(FromArgs)]
+/// Write a recorded search tree as a Graphviz DOT graph
+#[argh(subcommand, name = "dot")]
+struct DotArgs {
+    /// recording file written by a solve with --record and --outfile
+    #[argh(positional)]
+    recording: PathBuf,
+
+    /// write the graph to this file instead of stdout
+    #[argh(option, short = 'o')]
+    output: Option<PathBuf>,
+
+    /// collapse the subtrees below this depth into single nodes
+    #[argh(option)]
+    depth: Option<usize>,
+}
+
+#[derive(FromArgs)]
+/// Step through a recorded search, node by node
+#[argh(subcommand, name = "replay")]
+struct ReplayArgs {
+    /// recording file written by a solve with --record and --outfile (use --record full
+    /// to see the constraints)
+    #[argh(positional)]
+    recording: PathBuf,
+}
+
+/// Logs the progress of a solve to stderr
+struct ProgressLogger {
+    start: Instant,
+}
+
+impl<T: Display> SolveObserver<T> for ProgressLogger {
+    fn on_incumbent(&mut self, solution: &Solution<T>, nodes: usize) -> Control {
+        eprintln!(
+            "{:>12.3?} {nodes:>12} nodes  new incumbent: {}",
+            self.start.elapsed(),
+            solution.objective
+        );
+        Control::Continue
+    }
+
+    fn on_progress(&mut self, nodes: usize, depth: usize, best: Option<T>) -> Control {
+        let best = best.map_or("-".to_string(), |best| best.to_string());
+        eprintln!(
+            "{:>12.3?} {nodes:>12} nodes  depth: {depth:>4}  best: {best}",
+            self.start.elapsed()
+        );
+        Control::Continue
+    }
+
+    fn progress_interval(&self) -> usize {
+        1 << 20
+    }
+}
+
+/// Write the graph of a recorded search
+fn write_graph(args: &DotArgs) -> Result<()> {
+    let recording = SearchRecording::read(&args.recording)?;
+    match &args.output {
+        Some(output) => {
+            let mut out = BufWriter::new(File::create(output)?);
+            write_dot(&recording, args.depth, &mut out)?;
+            out.flush()?;
+        }
+        None => write_dot(&recording, args.depth, &mut std::io::stdout().lock())?,
+    }
+    Ok(())
 }
 
 fn main() -> Result<()> {
     let args: Args = argh::from_env();
 
-    let mut balas = Balas::from_lp(&args.infile)?;
+    match &args.command {
+        Some(Command::Dot(dot)) => return write_graph(dot),
+        Some(Command::Replay(args)) => {
+            let recording = SearchRecording::read(&args.recording)?;
+            replay(
+                &recording,
+                &mut std::io::stdin().lock(),
+                &mut std::io::stdout().lock(),
+            )?;
+            return Ok(());
+        }
+        None => {}
+    }
+    let Some(infile) = &args.infile else {
+        bail!("no input file given (see --help)");
+    };
+    if args.pool == Some(0) {
+        bail!("--pool has to be at least 1");
+    }
+    if args.pool.is_some() && (args.threads.is_some() || args.recursive || args.progress) {
+        bail!("--pool cannot be combined with --threads, --recursive or --progress");
+    }
+    if args.threads.is_some() && (args.recursive || args.progress) {
+        bail!("--threads cannot be combined with --recursive or --progress");
+    }
+    let is_mps = infile
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mps"));
+    let read_model = || {
+        if args.fixed_mps {
+            Model::from_fixed_mps(infile)
+        } else if is_mps {
+            Model::from_mps(infile)
+        } else {
+            Model::from_lp(infile)
+        }
+    };
+    if let Some(solfile) = &args.verify {
+        let verification = read_model()?.verify(&read_values(solfile)?)?;
+        println!("{verification}");
+        if !verification.is_valid() {
+            bail!("verification failed");
+        }
+        return Ok(());
+    }
+
+    let mut balas = if args.fixed_mps {
+        Balas::from_fixed_mps(infile)?
+    } else if is_mps {
+        Balas::from_mps(infile)?
+    } else {
+        Balas::from_lp(infile)?
+    };
+
+    let presolve = args.presolve.then(|| balas.presolve());
+
+    if let Some(priority) = &args.priority {
+        let priorities = read_values(priority)?.values;
+        balas.set_branch_order(&BranchOrder::Priority(priorities));
+    } else if args.order != BranchOrder::Cost {
+        balas.set_branch_order(&args.order);
+    }
+
+    if args.integer {
+        solve(balas.to_integer::<i64>()?, &args, presolve, read_model)
+    } else {
+        solve(balas, &args, presolve, read_model)
+    }
+}
+
+/// A number given on the command line, as a value of the solver's type
+fn value<T: NumCast + Copy>(value: f64, option: &str) -> Result<T> {
+    match num::cast::<f64, T>(value) {
+        Some(converted) if num::cast::<T, f64>(converted) == Some(value) => Ok(converted),
+        _ => bail!("--{option} has to be a whole number with --integer"),
+    }
+}
+
+/// Solve the problem as the arguments say, and report on it
+fn solve<T>(
+    mut balas: Balas<T>,
+    args: &Args,
+    presolve: Option<PresolveStats>,
+    read_model: impl Fn() -> Result<Model, LpErrors>,
+) -> Result<()>
+where
+    T: Bounded
+        + Neg<Output = T>
+        + Copy
+        + Display
+        + num::Zero
+        + num::ToPrimitive
+        + NumCast
+        + for<'a> std::ops::AddAssign<&'a T>
+        + for<'a> std::ops::SubAssign<&'a T>
+        + std::cmp::PartialOrd
+        + std::fmt::Debug
+        + Serialize
+        + Send
+        + Sync,
+    Vec<T>: FromIterator<<T as Neg>::Output>,
+{
+    balas.must_set = args.must_set;
+    balas.surrogate = args.surrogate;
+    balas.relaxation = args.relaxation;
+    balas.recording_level = args.record;
+    balas.limits.time_limit = args.time_limit.map(Duration::from_secs_f64);
+    balas.limits.node_limit = args.node_limit;
+    balas.limits.target = args
+        .target
+        .map(|target| value(target, "target"))
+        .transpose()?;
+
+    let start_solution = args.start.as_deref().map(read_assignment).transpose()?;
 
+    let mut pool = vec![];
     let start = Instant::now();
     for _ in 0..args.reps {
         balas.reset();
         if let Some(heuristic) = args.heuristic {
-            balas.best = heuristic.into();
+            balas.set_bound(value(heuristic, "heuristic")?);
         }
-        if args.recursive {
-            balas.solve_recursively();
+        if let Some(assignment) = &start_solution {
+            balas.warm_start(assignment)?;
+        }
+        let mut logger = ProgressLogger {
+            start: Instant::now(),
+        };
+        let observer: &mut dyn SolveObserver<T> = if args.progress {
+            &mut logger
+        } else {
+            &mut NoObserver
+        };
+        if let Some(capacity) = args.pool {
+            let tolerance = args
+                .pool_tolerance
+                .map(|tolerance| value(tolerance, "pool-tolerance"))
+                .transpose()?;
+            pool = balas.solve_pool(capacity, tolerance);
+        } else if let Some(threads) = args.threads {
+            balas.solve_parallel(threads, args.split);
+        } else if args.recursive {
+            balas.solve_recursively_with_observer(observer);
         } else {
-            balas.solve();
+            balas.solve_with_observer(observer);
         }
     }
     println!(
@@ -54,7 +365,60 @@ fn main() -> Result<()> {
         args.reps
     );
     balas.report();
-    if let Some(outfile) = args.outfile {
+    if let Some(stats) = presolve {
+        println!(
+            "Presolve: removed {} redundant and {} parallel rows, fixed {} forced and {} dominated variables",
+            stats.redundant_rows, stats.parallel_rows, stats.forced, stats.dominated
+        );
+        if stats.infeasible {
+            println!("Presolve: found a constraint that cannot be satisfied");
+        }
+    }
+    if args.must_set {
+        let stats = balas.must_set_stats;
+        println!(
+            "Must-set rule: fixed {} variables and pruned {} nodes",
+            stats.fixed, stats.pruned
+        );
+    }
+    if args.surrogate {
+        println!(
+            "Surrogate constraint: pruned {} nodes",
+            balas.surrogate_pruned
+        );
+    }
+    if args.relaxation {
+        println!("Relaxation bound: pruned {} nodes", balas.relaxation_pruned);
+    }
+    if args.pool.is_some() {
+        println!("Pool of {} solutions:", pool.len());
+        for solution in &pool {
+            let ones: Vec<_> = solution
+                .variables
+                .iter()
+                .filter(|(_, value)| *value == 1)
+                .map(|(var, _)| var.as_str())
+                .collect();
+            println!("  {}: {}", solution.objective, ones.join(" "));
+        }
+    }
+    if args.check {
+        if let Some(solution) = balas.best_solution() {
+            let solution = Solution {
+                objective: solution.objective.to_f64().unwrap_or(f64::NAN),
+                variables: solution.variables,
+            };
+            let verification = read_model()?.verify_solution(&solution)?;
+            println!("{verification}");
+            if !verification.is_valid() {
+                bail!("the solver's solution failed verification");
+            }
+        }
+    }
+    if let Some(solfile) = &args.solfile {
+        write_solution(&balas.result(), solfile, args.format)?;
+    }
+    if let Some(outfile) = &args.outfile {
         let mut out = File::create(outfile)?;
         let buf = serde_json::to_string(&balas)?;
         out.write(buf.as_bytes())?;
@@ -0,0 +1,584 @@
+use crate::extras::Extras;
+use crate::lp_errors::LpErrors;
+use crate::Balas;
+use lp_parser_rs::model::coefficient::Coefficient;
+use lp_parser_rs::model::constraint::Constraint;
+use lp_parser_rs::model::lp_problem::LPProblem;
+use lp_parser_rs::model::objective::Objective;
+use lp_parser_rs::model::sense::{Cmp, Sense};
+use lp_parser_rs::model::variable::Variable;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// The two flavors of MPS.  Fixed MPS places every field in a fixed range of
+/// columns (which allows spaces inside of names), while free MPS separates the
+/// fields by whitespace.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum MpsFormat {
+    Fixed,
+    Free,
+}
+
+impl Balas<f64> {
+    /// Read a problem in free MPS format.  Fixed MPS files whose names do not
+    /// contain spaces (which is nearly all of them) can be read this way as well.
+    pub fn from_mps(mps_path: &Path) -> Result<Balas<f64>, LpErrors> {
+        let code = fs::read_to_string(mps_path).map_err(LpErrors::FileReadError)?;
+        let (lp, extras) = parse_mps(&code, MpsFormat::Free)?;
+        Self::from_lp_problem(&lp, &extras)
+    }
+
+    /// Read a problem in fixed MPS format, where the fields are found by column position.
+    pub fn from_fixed_mps(mps_path: &Path) -> Result<Balas<f64>, LpErrors> {
+        let code = fs::read_to_string(mps_path).map_err(LpErrors::FileReadError)?;
+        let (lp, extras) = parse_mps(&code, MpsFormat::Fixed)?;
+        Self::from_lp_problem(&lp, &extras)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Start,
+    ObjSense,
+    Rows,
+    Columns,
+    Rhs,
+    Ranges,
+    Bounds,
+    End,
+}
+
+struct Row {
+    name: String,
+    sense: Cmp,
+    coefficients: Vec<Coefficient>,
+    rhs: f64,
+    range: Option<f64>,
+}
+
+struct Column {
+    integer: bool,
+    lower: f64,
+    upper: Option<f64>,
+}
+
+#[derive(Default)]
+struct MpsProblem {
+    name: String,
+    sense: Option<Sense>,
+    objective_name: Option<String>,
+    objective: Vec<Coefficient>,
+    objective_constant: f64,
+    free_rows: HashSet<String>,
+    rows: Vec<Row>,
+    row_index: HashMap<String, usize>,
+    columns: Vec<String>,
+    column_info: HashMap<String, Column>,
+}
+
+fn parse_error(line: usize, reason: impl Into<String>) -> LpErrors {
+    LpErrors::MPSParseError {
+        line,
+        reason: reason.into(),
+    }
+}
+
+fn parse_number(line: usize, field: &str) -> Result<f64, LpErrors> {
+    field
+        .parse()
+        .map_err(|_| parse_error(line, format!("expected a number, found \"{field}\"")))
+}
+
+/// Split a data line into its fields.  Empty fields are dropped, so that both
+/// formats yield the same list of tokens.
+fn fields(line: &str, format: MpsFormat) -> Vec<String> {
+    match format {
+        MpsFormat::Free => line.split_whitespace().map(|f| f.to_string()).collect(),
+        MpsFormat::Fixed => {
+            let chars: Vec<char> = line.chars().collect();
+            [(1, 3), (4, 12), (14, 22), (24, 36), (39, 47), (49, 61)]
+                .iter()
+                .filter_map(|&(start, end)| {
+                    let start = start.min(chars.len());
+                    let end = end.min(chars.len());
+                    let field: String = chars[start..end].iter().collect();
+                    let field = field.trim();
+                    (!field.is_empty()).then(|| field.to_string())
+                })
+                .collect()
+        }
+    }
+}
+
+/// Parse the MPS code, returning the problem along with its objective constant, ranges
+/// and fixed variables
+pub(crate) fn parse_mps(code: &str, format: MpsFormat) -> Result<(LPProblem, Extras), LpErrors> {
+    let mut mps = MpsProblem::default();
+    let mut section = Section::Start;
+    let mut integer_block = false;
+
+    for (i, line) in code.lines().enumerate() {
+        let line_no = i + 1;
+        if line.trim().is_empty() || line.starts_with('*') {
+            continue;
+        }
+
+        // Section headers start in the first column; data lines are indented.
+        if !line.starts_with(char::is_whitespace) {
+            let mut words = line.split_whitespace();
+            let header = words.next().unwrap_or_default().to_uppercase();
+            section = match header.as_str() {
+                "NAME" => {
+                    mps.name = words.collect::<Vec<_>>().join(" ");
+                    Section::Start
+                }
+                "OBJSENSE" => {
+                    // free MPS allows the sense on the same line as the header
+                    if let Some(sense) = words.next() {
+                        mps.sense = Some(parse_sense(line_no, sense)?);
+                    }
+                    Section::ObjSense
+                }
+                "ROWS" => Section::Rows,
+                "COLUMNS" => Section::Columns,
+                "RHS" => Section::Rhs,
+                "RANGES" => Section::Ranges,
+                "BOUNDS" => Section::Bounds,
+                "ENDATA" => Section::End,
+                _ => {
+                    return Err(parse_error(
+                        line_no,
+                        format!("unknown section \"{header}\""),
+                    ))
+                }
+            };
+            if section == Section::End {
+                break;
+            }
+            continue;
+        }
+
+        let fields = fields(line, format);
+        match section {
+            Section::Start | Section::End => {
+                return Err(parse_error(line_no, "data found outside of a section"))
+            }
+            Section::ObjSense => match fields.first() {
+                Some(sense) => mps.sense = Some(parse_sense(line_no, sense)?),
+                None => return Err(parse_error(line_no, "expected an objective sense")),
+            },
+            Section::Rows => mps.add_row(line_no, &fields)?,
+            Section::Columns => {
+                if fields.get(1).map(|f| f.as_str()) == Some("'MARKER'") {
+                    match fields.get(2).map(|f| f.as_str()) {
+                        Some("'INTORG'") => integer_block = true,
+                        Some("'INTEND'") => integer_block = false,
+                        _ => return Err(parse_error(line_no, "unknown MARKER type")),
+                    }
+                } else {
+                    mps.add_column_entries(line_no, &fields, integer_block)?;
+                }
+            }
+            Section::Rhs => mps.add_rhs(line_no, &fields)?,
+            Section::Ranges => mps.add_range(line_no, &fields)?,
+            Section::Bounds => mps.add_bound(line_no, &fields)?,
+        }
+    }
+
+    if section != Section::End {
+        return Err(parse_error(code.lines().count(), "missing ENDATA"));
+    }
+    mps.into_lp_problem()
+}
+
+fn parse_sense(line: usize, sense: &str) -> Result<Sense, LpErrors> {
+    match sense.to_uppercase().as_str() {
+        "MIN" | "MINIMIZE" => Ok(Sense::Minimize),
+        "MAX" | "MAXIMIZE" => Ok(Sense::Maximize),
+        _ => Err(parse_error(
+            line,
+            format!("unknown objective sense \"{sense}\""),
+        )),
+    }
+}
+
+impl MpsProblem {
+    fn add_row(&mut self, line: usize, fields: &[String]) -> Result<(), LpErrors> {
+        let [kind, name] = fields else {
+            return Err(parse_error(line, "expected a row type and a row name"));
+        };
+        let sense = match kind.to_uppercase().as_str() {
+            "N" => {
+                // Only the first free row is the objective; any others are ignored.
+                if self.objective_name.is_none() {
+                    self.objective_name = Some(name.clone());
+                } else {
+                    self.free_rows.insert(name.clone());
+                }
+                return Ok(());
+            }
+            "G" => Cmp::GreaterOrEqual,
+            "L" => Cmp::LessOrEqual,
+            "E" => Cmp::Equal,
+            _ => return Err(parse_error(line, format!("unknown row type \"{kind}\""))),
+        };
+        if self.row_index.contains_key(name) {
+            return Err(parse_error(line, format!("duplicate row \"{name}\"")));
+        }
+        self.row_index.insert(name.clone(), self.rows.len());
+        self.rows.push(Row {
+            name: name.clone(),
+            sense,
+            coefficients: vec![],
+            rhs: 0.0,
+            range: None,
+        });
+        Ok(())
+    }
+
+    fn add_column_entries(
+        &mut self,
+        line: usize,
+        fields: &[String],
+        integer: bool,
+    ) -> Result<(), LpErrors> {
+        if fields.len() != 3 && fields.len() != 5 {
+            return Err(parse_error(
+                line,
+                "expected a column name followed by one or two row/value pairs",
+            ));
+        }
+        let column = &fields[0];
+        if !self.column_info.contains_key(column) {
+            self.columns.push(column.clone());
+            self.column_info.insert(
+                column.clone(),
+                Column {
+                    integer,
+                    lower: 0.0,
+                    // by convention, integers between markers are binary unless
+                    // their bounds say otherwise
+                    upper: integer.then_some(1.0),
+                },
+            );
+        }
+        for pair in fields[1..].chunks(2) {
+            let coefficient = Coefficient {
+                var_name: column.clone(),
+                coefficient: parse_number(line, &pair[1])?,
+            };
+            if Some(&pair[0]) == self.objective_name.as_ref() {
+                self.objective.push(coefficient);
+            } else if let Some(&row) = self.row_index.get(&pair[0]) {
+                self.rows[row].coefficients.push(coefficient);
+            } else if !self.free_rows.contains(&pair[0]) {
+                return Err(parse_error(line, format!("unknown row \"{}\"", pair[0])));
+            }
+        }
+        Ok(())
+    }
+
+    /// The RHS and RANGES entries have an optional set name, followed by one or two
+    /// row/value pairs.  Returns the pairs with the values parsed.
+    fn row_values(line: usize, fields: &[String]) -> Result<Vec<(&str, f64)>, LpErrors> {
+        let pairs = match fields.len() {
+            2 | 4 => fields,
+            3 | 5 => &fields[1..],
+            _ => return Err(parse_error(line, "expected one or two row/value pairs")),
+        };
+        pairs
+            .chunks(2)
+            .map(|pair| Ok((pair[0].as_str(), parse_number(line, &pair[1])?)))
+            .collect()
+    }
+
+    fn add_rhs(&mut self, line: usize, fields: &[String]) -> Result<(), LpErrors> {
+        for (name, value) in Self::row_values(line, fields)? {
+            if Some(name) == self.objective_name.as_deref() {
+                // By convention, the RHS of the objective is the negated objective constant
+                self.objective_constant = -value;
+                continue;
+            }
+            if self.free_rows.contains(name) {
+                continue;
+            }
+            let row = self.find_row(line, name)?;
+            self.rows[row].rhs = value;
+        }
+        Ok(())
+    }
+
+    fn add_range(&mut self, line: usize, fields: &[String]) -> Result<(), LpErrors> {
+        for (name, value) in Self::row_values(line, fields)? {
+            let row = self.find_row(line, name)?;
+            self.rows[row].range = Some(value);
+        }
+        Ok(())
+    }
+
+    fn find_row(&self, line: usize, name: &str) -> Result<usize, LpErrors> {
+        self.row_index
+            .get(name)
+            .copied()
+            .ok_or_else(|| parse_error(line, format!("unknown row \"{name}\"")))
+    }
+
+    fn add_bound(&mut self, line: usize, fields: &[String]) -> Result<(), LpErrors> {
+        let kind = fields.first().map(|k| k.to_uppercase()).unwrap_or_default();
+        let takes_value = !matches!(kind.as_str(), "BV" | "FR" | "MI" | "PL");
+        // the bound set name is optional
+        let expected = if takes_value { 3 } else { 2 };
+        // many writers give BV a value as well, which says nothing more
+        let bv_value = |value: &String| kind == "BV" && value.parse::<f64>().is_ok();
+        let rest = match fields.len() {
+            3 if bv_value(&fields[2]) && self.column_info.contains_key(&fields[1]) => &fields[1..2],
+            4 if bv_value(&fields[3]) => &fields[2..3],
+            n if n == expected => &fields[1..],
+            n if n == expected + 1 => &fields[2..],
+            _ => return Err(parse_error(line, "wrong number of fields in bound")),
+        };
+        let value = if takes_value {
+            parse_number(line, &rest[1])?
+        } else {
+            0.0
+        };
+        let Some(column) = self.column_info.get_mut(&rest[0]) else {
+            return Err(parse_error(line, format!("unknown column \"{}\"", rest[0])));
+        };
+
+        match kind.as_str() {
+            "UP" => column.upper = Some(value),
+            "LO" => column.lower = value,
+            "FX" => {
+                column.lower = value;
+                column.upper = Some(value);
+            }
+            "BV" => {
+                column.integer = true;
+                column.lower = 0.0;
+                column.upper = Some(1.0);
+            }
+            "UI" => {
+                column.integer = true;
+                column.upper = Some(value);
+            }
+            "LI" => {
+                column.integer = true;
+                column.lower = value;
+            }
+            "FR" | "MI" => column.lower = f64::NEG_INFINITY,
+            "PL" => column.upper = None,
+            _ => return Err(parse_error(line, format!("unknown bound type \"{kind}\""))),
+        }
+        Ok(())
+    }
+
+    /// Convert the MPS data to the same model that the LP reader produces.  Ranged rows
+    /// become `>=` rows on their lower side, with the upper side kept in the `Extras`
+    /// along with the binaries fixed by their bounds.
+    fn into_lp_problem(self) -> Result<(LPProblem, Extras), LpErrors> {
+        let objective_name = self.objective_name.ok_or(LpErrors::NoObjective)?;
+        let mut extras = Extras {
+            objective_constant: self.objective_constant,
+            ..Default::default()
+        };
+
+        let mut variables = HashMap::new();
+        let mut constraints = HashMap::new();
+        for name in &self.columns {
+            let column = &self.column_info[name];
+            let binary = column.integer
+                && (column.lower == 0.0 || column.lower == 1.0)
+                && matches!(column.upper, Some(upper) if (upper == 0.0 || upper == 1.0) && upper >= column.lower);
+            let variable = if binary {
+                Variable::Binary
+            } else if column.integer {
+                Variable::General
+            } else {
+                Variable::Free
+            };
+            if binary && Some(column.lower) == column.upper {
+                extras.fixed.insert(name.clone(), column.lower as u8);
+            }
+            variables.insert(name.clone(), variable);
+        }
+
+        for row in self.rows {
+            // See the MPS description of RANGES for the meaning of R on each row type
+            let bounds = match (&row.sense, row.range) {
+                (_, None) => None,
+                (Cmp::GreaterOrEqual, Some(r)) => Some((row.rhs, row.rhs + r.abs())),
+                (Cmp::LessOrEqual, Some(r)) => Some((row.rhs - r.abs(), row.rhs)),
+                (_, Some(r)) if r < 0.0 => Some((row.rhs + r, row.rhs)),
+                (_, Some(r)) => Some((row.rhs, row.rhs + r)),
+            };
+            if let Some((lower, upper)) = bounds {
+                extras.ranges.insert(row.name.clone(), upper);
+                constraints.insert(
+                    row.name.clone(),
+                    Constraint::Standard {
+                        name: row.name,
+                        coefficients: row.coefficients,
+                        sense: Cmp::GreaterOrEqual,
+                        rhs: lower,
+                    },
+                );
+            } else {
+                constraints.insert(
+                    row.name.clone(),
+                    Constraint::Standard {
+                        name: row.name,
+                        coefficients: row.coefficients,
+                        sense: row.sense,
+                        rhs: row.rhs,
+                    },
+                );
+            }
+        }
+
+        let lp = LPProblem {
+            problem_name: self.name,
+            problem_sense: self.sense.unwrap_or(Sense::Minimize),
+            variables,
+            objectives: vec![Objective {
+                name: objective_name,
+                coefficients: self.objective,
+            }],
+            constraints,
+        };
+        Ok((lp, extras))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable<'a>(lp: &'a LPProblem, name: &str) -> &'a Variable {
+        &lp.variables[name]
+    }
+
+    /// The sense and right-hand side of a constraint
+    fn row(lp: &LPProblem, name: &str) -> (Cmp, f64) {
+        match &lp.constraints[name] {
+            Constraint::Standard { sense, rhs, .. } => (sense.clone(), *rhs),
+            _ => panic!("{name} is not a standard constraint"),
+        }
+    }
+
+    #[test]
+    fn reads_bv_bounds_with_and_without_values() {
+        let code = "\
+NAME bv
+ROWS
+ N obj
+ G c1
+COLUMNS
+ x obj 1 c1 1
+ y obj 2 c1 1
+ z obj 3 c1 1
+RHS
+ rhs c1 2
+BOUNDS
+ BV bnd x 1
+ BV y 1
+ BV bnd z
+ENDATA
+";
+        let (lp, _) = parse_mps(code, MpsFormat::Free).unwrap();
+        for name in ["x", "y", "z"] {
+            assert!(matches!(variable(&lp, name), Variable::Binary), "{name}");
+        }
+    }
+
+    #[test]
+    fn marker_integers_default_to_binary() {
+        let code = "\
+NAME markers
+ROWS
+ N obj
+ G c1
+COLUMNS
+ m1 'MARKER' 'INTORG'
+ x obj 1 c1 1
+ y obj 2 c1 1
+ m2 'MARKER' 'INTEND'
+ z obj 3 c1 1
+RHS
+ rhs c1 1
+ENDATA
+";
+        let (lp, _) = parse_mps(code, MpsFormat::Free).unwrap();
+        assert!(matches!(variable(&lp, "x"), Variable::Binary));
+        assert!(matches!(variable(&lp, "y"), Variable::Binary));
+        assert!(matches!(variable(&lp, "z"), Variable::Free));
+
+        // a bound above 1 still makes a general integer
+        let general = code.replace("ENDATA", "BOUNDS\n UP bnd y 5\nENDATA");
+        let (lp, _) = parse_mps(&general, MpsFormat::Free).unwrap();
+        assert!(matches!(variable(&lp, "y"), Variable::General));
+    }
+
+    #[test]
+    fn reads_ranges_on_every_row_type() {
+        let code = "\
+NAME ranges
+ROWS
+ N obj
+ G total
+ E link
+ E pair
+ L last
+COLUMNS
+ x obj 3 total 1
+ x link 1 last 1
+ y obj 2 total 1
+ y pair 1
+ z obj 1 total 1
+ z link -1 pair 1
+RHS
+ rhs total 1 pair 1
+ rhs last 2
+RANGES
+ rng total 1 link -1
+ rng pair 1 last 2
+ENDATA
+";
+        let (lp, extras) = parse_mps(code, MpsFormat::Free).unwrap();
+        // G: [rhs, rhs + |R|], L: [rhs - |R|, rhs], E: [rhs + R, rhs] or [rhs, rhs + R]
+        let expected = [
+            ("total", 1.0, 2.0),
+            ("link", -1.0, 0.0),
+            ("pair", 1.0, 2.0),
+            ("last", 0.0, 2.0),
+        ];
+        for (name, lower, upper) in expected {
+            assert_eq!(row(&lp, name), (Cmp::GreaterOrEqual, lower), "{name}");
+            assert_eq!(extras.ranges[name], upper, "{name}");
+        }
+    }
+
+    #[test]
+    fn reads_names_with_spaces_in_fixed_format() {
+        let code = "\
+NAME          FIXED
+ROWS
+ N  cost
+ G  pick one
+COLUMNS
+    my x      cost      1              pick one  1
+    my y      cost      2              pick one  1
+RHS
+    rhs       pick one  1
+BOUNDS
+ BV bnd       my x      1
+ BV bnd       my y
+ENDATA
+";
+        let (lp, _) = parse_mps(code, MpsFormat::Fixed).unwrap();
+        assert!(matches!(variable(&lp, "my x"), Variable::Binary));
+        assert!(matches!(variable(&lp, "my y"), Variable::Binary));
+        assert_eq!(row(&lp, "pick one"), (Cmp::GreaterOrEqual, 1.0));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::Array;
+
+/// How much the must-set rule did during the last solve
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MustSetStats {
+    /// Variables that were fixed because a constraint could not be satisfied otherwise
+    pub fixed: usize,
+    /// Nodes whose descendants were all skipped because the fixings ran into a
+    /// contradiction (or into the bound on the objective)
+    pub pruned: usize,
+}
+
+/// The must-set rule from Balas' paper.  At each node, a constraint whose best
+/// possible completion only just satisfies it forces the free variables that it depends
+/// on: a variable with a positive coefficient larger than the margin must be 1, and one
+/// with a negative coefficient larger than the margin must be 0.  Fixing a variable
+/// shrinks the margins of the other constraints, so this is repeated until nothing
+/// changes or some constraint can no longer be satisfied.
+pub(crate) struct MustSet<T> {
+    /// The constraints by row (the solver keeps them by column)
+    rows: Array<T>,
+    /// The largest coefficient (in absolute value) of each row.  A row with at least
+    /// this much margin cannot force anything.
+    largest: Vec<T>,
+    /// The forced value of each column
+    pub(crate) fixed: Vec<Option<u8>>,
+    /// (index of the node that fixed it, column) for each fixed column, so that the
+    /// fixings can be undone when the search leaves that node
+    trail: Vec<(usize, usize)>,
+    /// For each row, the largest value the left-hand side minus the right-hand side
+    /// can still reach
+    margin: Vec<T>,
+    pub(crate) stats: MustSetStats,
+}
+
+impl<T> MustSet<T>
+where
+    T: Copy
+        + num::Zero
+        + std::ops::Neg<Output = T>
+        + for<'a> std::ops::AddAssign<&'a T>
+        + for<'a> std::ops::SubAssign<&'a T>
+        + PartialOrd,
+{
+    pub(crate) fn new(constraints: &Array<T>) -> MustSet<T> {
+        let num_rows = constraints.first().map_or(0, |column| column.len());
+        let rows: Array<T> = (0..num_rows)
+            .map(|i| constraints.iter().map(|column| column[i]).collect())
+            .collect();
+        let largest = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&a| if a < T::zero() { -a } else { a })
+                    .fold(
+                        T::zero(),
+                        |largest, a| if a > largest { a } else { largest },
+                    )
+            })
+            .collect();
+        MustSet {
+            rows,
+            largest,
+            fixed: vec![None; constraints.len()],
+            trail: vec![],
+            margin: vec![T::zero(); num_rows],
+            stats: MustSetStats::default(),
+        }
+    }
+
+    /// Undo the fixings made at the node at `index` and below it
+    pub(crate) fn undo(&mut self, index: usize) {
+        while let Some(&(level, column)) = self.trail.last() {
+            if level < index {
+                break;
+            }
+            self.fixed[column] = None;
+            self.trail.pop();
+        }
+    }
+
+    /// Apply the fixing of `column` to the margins
+    fn fix(&mut self, column: &[T], value: u8) {
+        for (m, &a) in self.margin.iter_mut().zip(column) {
+            match value {
+                // the column's contribution is no longer optional
+                1 if a < T::zero() => *m += &a,
+                // nor is it available
+                0 if a > T::zero() => *m -= &a,
+                _ => {}
+            }
+        }
+    }
+
+    /// Fix the free columns after `index` that are forced, given the `accumulator` and
+    /// `objective` at the node and the `remaining` positive coefficients of the columns
+    /// after it.  Returns false if the node has no feasible descendant that `prunes`
+    /// allows.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn propagate(
+        &mut self,
+        index: usize,
+        constraints: &Array<T>,
+        coefficients: &[T],
+        accumulator: &[T],
+        remaining: &[T],
+        objective: T,
+        prunes: impl Fn(T) -> bool,
+    ) -> bool {
+        self.margin.clear();
+        self.margin
+            .extend(accumulator.iter().zip(remaining).map(|(&a, &r)| {
+                let mut m = a;
+                m += &r;
+                m
+            }));
+        // fixings made at ancestors that still lie ahead
+        let mut bound = objective;
+        for t in 0..self.trail.len() {
+            let column = self.trail[t].1;
+            if column > index {
+                let value = self.fixed[column].unwrap_or_default();
+                self.fix(&constraints[column], value);
+                if value == 1 {
+                    bound += &coefficients[column];
+                }
+            }
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for i in 0..self.rows.len() {
+                if self.margin[i] < T::zero() {
+                    self.stats.pruned += 1;
+                    return false;
+                }
+                if self.margin[i] >= self.largest[i] {
+                    continue;
+                }
+                for j in index + 1..coefficients.len() {
+                    if self.fixed[j].is_some() {
+                        continue;
+                    }
+                    let a = self.rows[i][j];
+                    let m = self.margin[i];
+                    let value = if a > m {
+                        1
+                    } else if -a > m {
+                        0
+                    } else {
+                        continue;
+                    };
+                    self.fixed[j] = Some(value);
+                    self.trail.push((index, j));
+                    self.stats.fixed += 1;
+                    self.fix(&constraints[j], value);
+                    changed = true;
+                    if value == 1 {
+                        bound += &coefficients[j];
+                        if prunes(bound) {
+                            self.stats.pruned += 1;
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+}
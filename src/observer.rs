@@ -0,0 +1,101 @@
+use crate::{Balas, Limit, Solution, SolveResult, Status};
+use num::Bounded;
+use std::{fmt::Display, ops::Neg};
+
+/// Returned by the observer callbacks to let the search continue or to stop it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Control {
+    Continue,
+    Abort,
+}
+
+/// Receives events while a solve is running.  Every method has a default that does
+/// nothing, so an observer only needs to implement the events it is interested in.
+/// Aborting from a callback ends the solve with `Status::Interrupted`.
+pub trait SolveObserver<T> {
+    /// A better solution was found after examining `nodes` nodes
+    fn on_incumbent(&mut self, _solution: &Solution<T>, _nodes: usize) -> Control {
+        Control::Continue
+    }
+
+    /// Called every `progress_interval` nodes with the current depth in the tree and
+    /// the objective value of the best solution so far
+    fn on_progress(&mut self, _nodes: usize, _depth: usize, _best: Option<T>) -> Control {
+        Control::Continue
+    }
+
+    /// The solve is over
+    fn on_finish(&mut self, _result: &SolveResult<T>) {}
+
+    /// How many nodes are examined between calls to `on_progress`
+    fn progress_interval(&self) -> usize {
+        1 << 16
+    }
+}
+
+/// An observer that ignores every event
+pub struct NoObserver;
+
+impl<T> SolveObserver<T> for NoObserver {}
+
+impl<T> Balas<T>
+where
+    T: Bounded
+        + Neg<Output = T>
+        + Copy
+        + Display
+        + num::Zero
+        + num::ToPrimitive
+        + for<'a> std::ops::AddAssign<&'a T>
+        + for<'a> std::ops::SubAssign<&'a T>
+        + std::cmp::PartialOrd
+        + std::fmt::Debug,
+    Vec<T>: FromIterator<<T as Neg>::Output>,
+{
+    /// Report progress if it is due.  Returns a status if the search has to stop.
+    pub(crate) fn progress(
+        &mut self,
+        depth: usize,
+        observer: &mut dyn SolveObserver<T>,
+    ) -> Option<Status> {
+        if self.count < self.next_progress {
+            return None;
+        }
+        self.next_progress = self.count + observer.progress_interval();
+        let best = (!self.solution.is_empty()).then(|| self.transform.objective(self.best));
+        match observer.on_progress(self.count, depth, best) {
+            Control::Continue => None,
+            Control::Abort => Some(Status::Interrupted),
+        }
+    }
+
+    /// Called after a new incumbent has been stored in `best` and `solution`.  Returns
+    /// a status if the search has to stop.
+    pub(crate) fn new_incumbent(&mut self, observer: &mut dyn SolveObserver<T>) -> Option<Status> {
+        // a worker of a parallel solve counts the nodes of all of the workers
+        let node = match &self.shared {
+            Some(link) => link.nodes(self.count),
+            None => self.count,
+        };
+        let elapsed = self
+            .started
+            .map(|start| start.elapsed())
+            .unwrap_or_default();
+        let objective = self
+            .transform
+            .objective(self.best)
+            .to_f64()
+            .unwrap_or(f64::NAN);
+        self.stats.improved(node, elapsed, objective);
+        self.share_incumbent();
+        if let Some(solution) = self.best_solution() {
+            if observer.on_incumbent(&solution, self.count) == Control::Abort {
+                return Some(Status::Interrupted);
+            }
+        }
+        if self.limits.target_reached(&self.transform, self.best) {
+            return Some(Status::LimitReached(Limit::Target));
+        }
+        None
+    }
+}
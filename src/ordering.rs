@@ -0,0 +1,162 @@
+use crate::Balas;
+use num::Bounded;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::{fmt::Display, ops::Neg};
+
+/// The order in which the search branches on the variables.  Any order gives the same
+/// optimal value, but the number of nodes examined can differ enormously.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum BranchOrder {
+    /// Ascending objective coefficient (the classic rule)
+    #[default]
+    Cost,
+    /// Variables that help the most constraints first
+    Coverage,
+    /// Ascending ratio of cost to the total of the variable's positive constraint
+    /// coefficients, i.e. the cheapest help per unit first
+    Ratio,
+    /// A random order from the given seed
+    Random(u64),
+    /// Higher priorities first.  Variables that are not listed have priority 0.
+    Priority(HashMap<String, f64>),
+}
+
+impl FromStr for BranchOrder {
+    type Err = String;
+
+    /// Parse `cost`, `coverage`, `ratio`, `random` or `random:<seed>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        match s.split_once(':') {
+            Some(("random", seed)) => seed
+                .parse()
+                .map(BranchOrder::Random)
+                .map_err(|_| format!("bad random seed \"{seed}\"")),
+            None if s == "cost" => Ok(BranchOrder::Cost),
+            None if s == "coverage" => Ok(BranchOrder::Coverage),
+            None if s == "ratio" => Ok(BranchOrder::Ratio),
+            None if s == "random" => Ok(BranchOrder::Random(0)),
+            _ => Err(format!(
+                "unknown order \"{s}\" (expected cost, coverage, ratio or random[:seed])"
+            )),
+        }
+    }
+}
+
+/// A small, fast generator (SplitMix64), so that random orders are repeatable without
+/// depending on an external crate
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Sort by a key, with ties in ascending order of cost
+fn sort_by_key<K: PartialOrd>(order: &mut [usize], costs: &[f64], key: impl Fn(usize) -> K) {
+    order.sort_by(|&a, &b| {
+        key(a)
+            .partial_cmp(&key(b))
+            .unwrap_or(Ordering::Equal)
+            .then(costs[a].partial_cmp(&costs[b]).unwrap_or(Ordering::Equal))
+    });
+}
+
+impl<T> Balas<T>
+where
+    T: Bounded
+        + Neg<Output = T>
+        + Copy
+        + Display
+        + num::Zero
+        + num::ToPrimitive
+        + for<'a> std::ops::AddAssign<&'a T>
+        + for<'a> std::ops::SubAssign<&'a T>
+        + std::cmp::PartialOrd
+        + std::fmt::Debug,
+    Vec<T>: FromIterator<<T as Neg>::Output>,
+{
+    /// Change the order in which the search branches on the variables.  Solutions are
+    /// still reported in the order the variables were given.
+    pub fn set_branch_order(&mut self, branch_order: &BranchOrder) {
+        // everything below is indexed by the caller's order of the variables (some of
+        // which may have been taken out by `presolve`)
+        let mut column = vec![0; self.order.len() + self.removed.len()];
+        for (c, &original) in self.order.iter().enumerate() {
+            column[original] = c;
+        }
+        let to_f64 = |value: &T| value.to_f64().unwrap_or(f64::NAN);
+        let costs: Vec<f64> = column
+            .iter()
+            .map(|&c| to_f64(&self.coefficients[c]))
+            .collect();
+        let positive = |original: usize| {
+            self.constraints[column[original]]
+                .iter()
+                .filter(|&a| *a > T::zero())
+        };
+
+        let mut order = self.order.clone();
+        order.sort();
+        let num_vars = order.len();
+        match branch_order {
+            BranchOrder::Cost => sort_by_key(&mut order, &costs, |_| 0),
+            BranchOrder::Coverage => sort_by_key(&mut order, &costs, |i| {
+                std::cmp::Reverse(positive(i).count())
+            }),
+            BranchOrder::Ratio => {
+                let help: Vec<f64> = (0..column.len())
+                    .map(|i| positive(i).map(to_f64).sum())
+                    .collect();
+                sort_by_key(&mut order, &costs, |i| {
+                    if help[i] > 0.0 {
+                        costs[i] / help[i]
+                    } else {
+                        f64::INFINITY
+                    }
+                })
+            }
+            BranchOrder::Random(seed) => {
+                // Fisher-Yates
+                let mut rng = SplitMix64(*seed);
+                for i in (1..num_vars).rev() {
+                    order.swap(i, rng.below(i + 1));
+                }
+            }
+            BranchOrder::Priority(priorities) => sort_by_key(&mut order, &costs, |i| {
+                -priorities.get(&self.vars[i]).copied().unwrap_or(0.0)
+            }),
+        }
+        self.apply_order(order, &column);
+    }
+
+    /// Rearrange the columns into `order` (caller's indices), given the current column
+    /// of each of the caller's variables
+    fn apply_order(&mut self, order: Vec<usize>, column: &[usize]) {
+        self.coefficients = order
+            .iter()
+            .map(|&i| self.coefficients[column[i]])
+            .collect();
+        self.constraints = order
+            .iter()
+            .map(|&i| self.constraints[column[i]].clone())
+            .collect();
+        if !self.solution.is_empty() {
+            self.solution = order.iter().map(|&i| self.solution[column[i]]).collect();
+        }
+        self.update_cumulative();
+        self.order = order;
+    }
+}
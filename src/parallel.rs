@@ -0,0 +1,379 @@
+use crate::limits::CHECK_INTERVAL;
+use crate::relaxation::Relaxation;
+use crate::{Balas, Limit, NoObserver, SearchStats, SolveResult, Status};
+use num::Bounded;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::{fmt::Display, ops::Neg};
+
+/// What the workers of a parallel solve share
+struct Shared<T> {
+    /// The best objective found by any worker (in terms of the whole problem)
+    best: Mutex<T>,
+    /// The best solution of the finished subproblems: (objective, columns)
+    incumbent: Mutex<Option<(T, Vec<u8>)>>,
+    /// Set when the solve has to stop early, along with the reason
+    stop: AtomicBool,
+    stopped: Mutex<Option<Status>>,
+    /// Nodes examined by all of the workers (reported every `CHECK_INTERVAL` nodes)
+    nodes: AtomicUsize,
+    node_limit: Option<usize>,
+    /// The multipliers of the relaxation's root bound, which the workers start from
+    multipliers: Vec<f64>,
+}
+
+impl<T> SharedLink<T> {
+    /// The nodes examined by all of the workers, given the `count` of this one (the
+    /// others are only as far as they have reported)
+    pub(crate) fn nodes(&self, count: usize) -> usize {
+        self.shared.nodes.load(Ordering::Relaxed) + count - self.reported
+    }
+
+    /// The multipliers that the relaxation of the worker starts from
+    pub(crate) fn multipliers(&self) -> &[f64] {
+        &self.shared.multipliers
+    }
+}
+
+impl<T> Shared<T> {
+    fn stop(&self, status: Status) {
+        self.stopped.lock().unwrap().get_or_insert(status);
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// A worker's connection to the shared state
+pub(crate) struct SharedLink<T> {
+    shared: Arc<Shared<T>>,
+    /// The cost of the fixed variables of the worker's subproblem, which converts its
+    /// objective values to those of the whole problem
+    offset: T,
+    /// Nodes already added to `shared.nodes`
+    reported: usize,
+}
+
+impl<T> Balas<T>
+where
+    T: Bounded
+        + Neg<Output = T>
+        + Copy
+        + Display
+        + num::Zero
+        + num::ToPrimitive
+        + for<'a> std::ops::AddAssign<&'a T>
+        + for<'a> std::ops::SubAssign<&'a T>
+        + std::cmp::PartialOrd
+        + std::fmt::Debug
+        + Send
+        + Sync,
+    Vec<T>: FromIterator<<T as Neg>::Output>,
+{
+    /// Solve on `threads` threads.  The first `split` variables (in branching order)
+    /// are fixed in every possible way, and the resulting subproblems are handed out to
+    /// the threads, which share the best objective found so far for pruning.  Without a
+    /// `split`, one is chosen that gives each thread several subproblems.
+    ///
+    /// The optimal objective is the same as that of `solve`, though a different
+    /// optimal solution may be found when there are ties.  The node limit is only
+    /// checked every so often by each thread, so it can be overshot a little.
+    pub fn solve_parallel(&mut self, threads: usize, split: Option<usize>) -> SolveResult<T> {
+        let start = Instant::now();
+        let num_vars = self.coefficients.len();
+        let threads = threads.max(1);
+        // at least 8 subproblems per thread, leaving at least one free variable
+        let split = split
+            .unwrap_or_else(|| (8 * threads).next_power_of_two().trailing_zeros() as usize)
+            .min(num_vars.saturating_sub(1))
+            .min(usize::BITS as usize - 1);
+
+        // the workers only bound their subproblems, so the bound of the whole problem
+        // is found here, as `solve` does
+        let accumulator: Vec<T> = self.rhs.iter().map(|&b| -b).collect();
+        let mut relaxation = self.relaxation.then(|| Relaxation::new(self.rhs.len()));
+        if let Some(relaxation) = &mut relaxation {
+            let bound =
+                relaxation.root_bound(&self.constraints, &self.coefficients, &accumulator, None);
+            self.root_bound = Some(bound);
+        }
+
+        let shared = Arc::new(Shared {
+            best: Mutex::new(self.best),
+            incumbent: Mutex::new(
+                (!self.solution.is_empty()).then(|| (self.best, self.solution.clone())),
+            ),
+            stop: AtomicBool::new(false),
+            stopped: Mutex::new(None),
+            nodes: AtomicUsize::new(0),
+            node_limit: self.limits.node_limit,
+            multipliers: relaxation
+                .as_ref()
+                .map_or(vec![], |relaxation| relaxation.root_multipliers().to_vec()),
+        });
+        let next = AtomicUsize::new(0);
+        let this = &*self;
+        let workers: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut worker = Worker::default();
+                        loop {
+                            let prefix = next.fetch_add(1, Ordering::Relaxed);
+                            if prefix >> split != 0 || shared.stop.load(Ordering::Relaxed) {
+                                break;
+                            }
+                            this.solve_subproblem(prefix, split, start, &shared, &mut worker);
+                        }
+                        worker
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        self.count = workers.iter().map(|w| w.count).sum();
+        self.must_set_stats.fixed = workers.iter().map(|w| w.must_set_fixed).sum();
+        self.must_set_stats.pruned = workers.iter().map(|w| w.must_set_pruned).sum();
+        self.surrogate_pruned = workers.iter().map(|w| w.surrogate_pruned).sum();
+        self.relaxation_pruned = workers.iter().map(|w| w.relaxation_pruned).sum();
+        for worker in &workers {
+            for (stats, started) in &worker.stats {
+                self.stats
+                    .merge(stats, split, *started, self.transform.negated);
+            }
+        }
+        if let Some((best, solution)) = shared.incumbent.lock().unwrap().take() {
+            self.best = best;
+            self.solution = solution;
+        }
+        if let Some(relaxation) = &mut relaxation {
+            // with an incumbent to aim for, the root bound can usually be improved
+            if !self.solution.is_empty() {
+                let bound = relaxation.root_bound(
+                    &self.constraints,
+                    &self.coefficients,
+                    &accumulator,
+                    self.best.to_f64(),
+                );
+                self.root_bound = self.root_bound.map(|root| root.max(bound));
+            }
+        }
+        let stopped = shared.stopped.lock().unwrap().clone();
+        self.finish(start, stopped, &mut NoObserver)
+    }
+
+    /// Solve the subproblem whose first `split` columns are given by the bits of
+    /// `prefix`, adding the outcome to the `shared` state and the statistics of `worker`
+    fn solve_subproblem(
+        &self,
+        prefix: usize,
+        split: usize,
+        start: Instant,
+        shared: &Arc<Shared<T>>,
+        worker: &mut Worker,
+    ) {
+        // the last fixed column varies fastest, as it would in the sequential search
+        let fixed: Vec<u8> = (0..split)
+            .map(|i| ((prefix >> (split - 1 - i)) & 1) as u8)
+            .collect();
+        let mut offset = T::zero();
+        let mut rhs = self.rhs.clone();
+        for (column, _) in fixed.iter().enumerate().filter(|(_, &value)| value == 1) {
+            offset += &self.coefficients[column];
+            rhs.iter_mut()
+                .zip(&self.constraints[column])
+                .for_each(|(b, a)| *b -= a);
+        }
+        let best = *shared.best.lock().unwrap();
+        if offset >= best {
+            // the whole subproblem is pruned by the bound at its root
+            let pruned = SearchStats {
+                pruned_by_bound: 1,
+                ..SearchStats::default()
+            };
+            worker.stats.push((pruned, start.elapsed()));
+            return;
+        }
+
+        let mut sub = Balas::from_columns(
+            self.coefficients[split..].to_vec(),
+            self.constraints[split..].to_vec(),
+            rhs,
+            vec![],
+            vec![],
+        );
+        sub.ranges = self.ranges.clone();
+        sub.transform.negated = self.transform.negated;
+        sub.transform.offset = self.transform.offset;
+        sub.transform.offset += &offset;
+        sub.limits.time_limit = self
+            .limits
+            .time_limit
+            .map(|limit| limit.saturating_sub(start.elapsed()));
+        sub.limits.target = self.limits.target;
+        sub.limits.cancel = self.limits.cancel.clone();
+        sub.must_set = self.must_set;
+        sub.surrogate = self.surrogate;
+        sub.relaxation = self.relaxation;
+        let mut sub_best = best;
+        sub_best -= &offset;
+        sub.best = sub_best;
+        sub.shared = Some(SharedLink {
+            shared: shared.clone(),
+            offset,
+            reported: 0,
+        });
+
+        let started = start.elapsed();
+        let result = sub.solve();
+        shared.nodes.fetch_add(
+            sub.count - sub.shared.as_ref().map_or(0, |link| link.reported),
+            Ordering::Relaxed,
+        );
+        worker.count += sub.count;
+        worker.must_set_fixed += sub.must_set_stats.fixed;
+        worker.must_set_pruned += sub.must_set_stats.pruned;
+        worker.surrogate_pruned += sub.surrogate_pruned;
+        worker.relaxation_pruned += sub.relaxation_pruned;
+        worker.stats.push((sub.stats.clone(), started));
+
+        match result.status {
+            Status::Optimal | Status::Infeasible => {}
+            // stopped because another worker stopped
+            Status::Interrupted if shared.stop.load(Ordering::Relaxed) => {}
+            status => shared.stop(status),
+        }
+        if !sub.solution.is_empty() {
+            let mut objective = sub.best;
+            objective += &offset;
+            let mut incumbent = shared.incumbent.lock().unwrap();
+            let better = match incumbent.as_ref() {
+                Some((best, _)) => objective < *best,
+                None => true,
+            };
+            if better {
+                let mut columns = fixed;
+                columns.extend_from_slice(&sub.solution);
+                *incumbent = Some((objective, columns));
+            }
+        }
+    }
+}
+
+impl<T> Balas<T>
+where
+    T: Bounded
+        + Neg<Output = T>
+        + Copy
+        + Display
+        + num::Zero
+        + num::ToPrimitive
+        + for<'a> std::ops::AddAssign<&'a T>
+        + for<'a> std::ops::SubAssign<&'a T>
+        + std::cmp::PartialOrd
+        + std::fmt::Debug,
+    Vec<T>: FromIterator<<T as Neg>::Output>,
+{
+    /// For a worker of a parallel solve, share the best objective with the other
+    /// workers and check whether the solve has to stop.  This is only done every
+    /// `CHECK_INTERVAL` nodes.
+    pub(crate) fn sync_shared(&mut self) -> Option<Status> {
+        let link = self.shared.as_mut()?;
+        if self.count & (CHECK_INTERVAL - 1) != 0 {
+            return None;
+        }
+        let shared = &link.shared;
+        if shared.stop.load(Ordering::Relaxed) {
+            return Some(Status::Interrupted);
+        }
+        let nodes = shared
+            .nodes
+            .fetch_add(self.count - link.reported, Ordering::Relaxed)
+            + self.count
+            - link.reported;
+        link.reported = self.count;
+        if shared.node_limit.is_some_and(|limit| nodes >= limit) {
+            let status = Status::LimitReached(Limit::Nodes);
+            shared.stop(status.clone());
+            return Some(status);
+        }
+
+        let mut best = *shared.best.lock().unwrap();
+        best -= &link.offset;
+        if best < self.best {
+            // another worker has done better, so our incumbent is of no use
+            self.best = best;
+            self.solution.clear();
+        }
+        None
+    }
+
+    /// For a worker of a parallel solve, let the others know about a new incumbent
+    pub(crate) fn share_incumbent(&self) {
+        if let Some(link) = &self.shared {
+            let mut objective = self.best;
+            objective += &link.offset;
+            let mut best = link.shared.best.lock().unwrap();
+            if objective < *best {
+                *best = objective;
+            }
+        }
+    }
+}
+
+/// The statistics of one thread
+#[derive(Default)]
+struct Worker {
+    count: usize,
+    must_set_fixed: usize,
+    must_set_pruned: usize,
+    surrogate_pruned: usize,
+    relaxation_pruned: usize,
+    /// The statistics of each subproblem, and when its search started
+    stats: Vec<(SearchStats, Duration)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Balas;
+    use std::path::Path;
+
+    #[test]
+    fn finds_the_sequential_objective() {
+        for sample in ["demo", "parallel", "v006c032", "v032c032"] {
+            let path = format!("samples/{sample}.lp");
+            let expected = Balas::from_lp(Path::new(&path)).unwrap().solve();
+            for (threads, split) in [(1, None), (2, Some(1)), (4, None), (3, Some(4))] {
+                let mut balas = Balas::from_lp(Path::new(&path)).unwrap();
+                let result = balas.solve_parallel(threads, split);
+                assert_eq!(result.status, expected.status, "{sample}");
+                assert_eq!(
+                    result.solution.map(|solution| solution.objective),
+                    expected
+                        .solution
+                        .as_ref()
+                        .map(|solution| solution.objective),
+                    "{sample} on {threads} threads"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bounds_the_subproblems_with_the_relaxation() {
+        let path = Path::new("samples/v032c032.lp");
+        let mut sequential = Balas::from_lp(path).unwrap();
+        sequential.relaxation = true;
+        let expected = sequential.solve();
+
+        let mut balas = Balas::from_lp(path).unwrap();
+        balas.relaxation = true;
+        let result = balas.solve_parallel(4, None);
+        assert_eq!(
+            result.solution.unwrap().objective,
+            expected.solution.unwrap().objective
+        );
+        let (bound, expected) = (result.bound.unwrap(), expected.bound.unwrap());
+        assert!((bound - expected).abs() < 1e-6, "{bound} {expected}");
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{Balas, Solution};
+use num::Bounded;
+use std::{fmt::Display, ops::Neg};
+
+/// A bounded collection of the best solutions found, used when more than one
+/// solution is wanted (see `Balas::solve_pool`).
+#[derive(Clone, Debug)]
+pub(crate) struct SolutionPool<T> {
+    capacity: usize,
+    tolerance: Option<T>,
+    /// (objective, columns) in ascending order of the solver's objective
+    entries: Vec<(T, Vec<u8>)>,
+}
+
+impl<T> SolutionPool<T>
+where
+    T: Copy + PartialOrd + for<'a> std::ops::AddAssign<&'a T>,
+{
+    pub(crate) fn new(capacity: usize, tolerance: Option<T>) -> SolutionPool<T> {
+        SolutionPool {
+            capacity,
+            tolerance,
+            entries: vec![],
+        }
+    }
+
+    /// The largest objective that can still make it into the pool
+    fn limit(&self) -> Option<T> {
+        let best = self.entries.first()?.0;
+        self.tolerance.map(|tolerance| {
+            let mut limit = best;
+            limit += &tolerance;
+            limit
+        })
+    }
+
+    /// Whether a node with this objective (or any of its descendants) can be skipped.
+    /// Unlike the usual bound, ties are not pruned unless the pool is full.
+    pub(crate) fn prunes(&self, objective: T) -> bool {
+        if self.limit().is_some_and(|limit| objective > limit) {
+            return true;
+        }
+        self.entries.len() >= self.capacity
+            && self
+                .entries
+                .last()
+                .is_some_and(|(worst, _)| objective >= *worst)
+    }
+
+    pub(crate) fn insert(&mut self, objective: T, columns: &[u8]) {
+        // the search finds a warm start again
+        if self.entries.iter().any(|(_, entry)| entry == columns) {
+            return;
+        }
+        let position = self
+            .entries
+            .iter()
+            .position(|(value, _)| objective < *value)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(position, (objective, columns.to_vec()));
+        self.entries.truncate(self.capacity);
+        if let Some(limit) = self.limit() {
+            self.entries.retain(|(value, _)| *value <= limit);
+        }
+    }
+}
+
+impl<T> Balas<T>
+where
+    T: Bounded
+        + Neg<Output = T>
+        + Copy
+        + Display
+        + num::Zero
+        + num::ToPrimitive
+        + for<'a> std::ops::AddAssign<&'a T>
+        + for<'a> std::ops::SubAssign<&'a T>
+        + std::cmp::PartialOrd
+        + std::fmt::Debug,
+    Vec<T>: FromIterator<<T as Neg>::Output>,
+{
+    /// Solve, keeping up to `capacity` of the best solutions instead of just one.  With a
+    /// `tolerance`, only solutions within that distance of the optimal objective are
+    /// kept, so a tolerance of zero enumerates every optimal solution.  The solutions
+    /// are returned best first; the usual result is available from `result()`.  A
+    /// solution from `warm_start` goes into the pool too.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn solve_pool(&mut self, capacity: usize, tolerance: Option<T>) -> Vec<Solution<T>> {
+        assert!(capacity > 0, "a solution pool needs room for a solution");
+        let mut pool = SolutionPool::new(capacity, tolerance);
+        if !self.solution.is_empty() {
+            pool.insert(self.best, &self.solution);
+        }
+        self.pool = Some(pool);
+        self.solve();
+        let pool = self.pool.take().expect("the pool is only removed here");
+        pool.entries
+            .iter()
+            .map(|(objective, columns)| self.to_solution(*objective, columns))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Balas;
+    use std::collections::HashMap;
+
+    /// Minimize y subject to x - y >= -1, where x = y = 0 and x = 1, y = 0 are optimal
+    fn two_optima() -> Balas<f64> {
+        let vars = vec!["x".to_string(), "y".to_string()];
+        Balas::new(&[0.0, 1.0], &vec![vec![1.0], vec![-1.0]], &[-1.0], &vars)
+    }
+
+    #[test]
+    fn keeps_the_warm_start() {
+        let mut balas = two_optima();
+        let all = balas.solve_pool(10, Some(0.0));
+        assert_eq!(all.len(), 2);
+
+        let mut balas = two_optima();
+        let start = HashMap::from([("x".to_string(), 0), ("y".to_string(), 0)]);
+        balas.warm_start(&start).unwrap();
+        let pool = balas.solve_pool(10, Some(0.0));
+        assert_eq!(pool.len(), 2);
+        assert!(all.iter().all(|solution| pool.contains(solution)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn needs_room_for_a_solution() {
+        two_optima().solve_pool(0, None);
+    }
+}
@@ -0,0 +1,381 @@
+use crate::Balas;
+use num::Bounded;
+use std::{fmt::Display, ops::Neg};
+
+/// What `presolve` did to the problem
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PresolveStats {
+    /// Rows that every assignment satisfies
+    pub redundant_rows: usize,
+    /// Rows dropped because a parallel row implies them
+    pub parallel_rows: usize,
+    /// Variables fixed because a row cannot be satisfied otherwise
+    pub forced: usize,
+    /// Variables fixed because the other value can never do better
+    pub dominated: usize,
+    /// Whether some row cannot be satisfied at all
+    pub infeasible: bool,
+}
+
+/// Parallel rows are compared in `f64`, so coefficients only need to agree this
+/// closely (relative to their size)
+const TOLERANCE: f64 = 1e-9;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+impl<T> Balas<T>
+where
+    T: Bounded
+        + Neg<Output = T>
+        + Copy
+        + Display
+        + num::Zero
+        + num::ToPrimitive
+        + for<'a> std::ops::AddAssign<&'a T>
+        + for<'a> std::ops::SubAssign<&'a T>
+        + std::cmp::PartialOrd
+        + std::fmt::Debug,
+    Vec<T>: FromIterator<<T as Neg>::Output>,
+{
+    /// Simplify the problem before searching.  Until nothing changes, this removes
+    /// rows that always hold, fixes variables that a single row forces, drops the
+    /// weaker of two parallel rows, and fixes variables that can only hurt (no positive
+    /// coefficients) or can only help (no cost and no negative coefficients), as long as
+    /// they are not in a row with a range.  The fixed variables leave the search, and
+    /// solutions are reported with their values filled back in.
+    ///
+    /// The optimal objective does not change, but when there are ties a different
+    /// optimal solution may be found, and a pool only holds solutions that agree with
+    /// the fixed values.  If a row cannot be satisfied, presolving stops there and the
+    /// search reports the problem as infeasible.  Any incumbent is discarded, so call
+    /// this before `warm_start` or `set_bound`.
+    pub fn presolve(&mut self) -> PresolveStats {
+        let mut stats = PresolveStats::default();
+        let num_vars = self.coefficients.len();
+        let num_rows = self.rhs.len();
+        let mut fixed: Vec<Option<u8>> = vec![None; num_vars];
+        let mut active = vec![true; num_rows];
+
+        let mut changed = true;
+        while changed && !stats.infeasible {
+            changed = false;
+            for (row, active) in active.iter_mut().enumerate() {
+                if !*active {
+                    continue;
+                }
+                // the range of the left-hand side over the free variables
+                let mut lowest = T::zero();
+                let mut highest = T::zero();
+                for (column, _) in fixed.iter().enumerate().filter(|(_, f)| f.is_none()) {
+                    let a = self.constraints[column][row];
+                    if a > T::zero() {
+                        highest += &a;
+                    } else {
+                        lowest += &a;
+                    }
+                }
+                // the most the left-hand side may be, for a row with a range
+                let top = self.ranges[row].map(|width| {
+                    let mut top = self.rhs[row];
+                    top += &width;
+                    top
+                });
+                let below_top = |value: T| match top {
+                    Some(top) => value <= top,
+                    None => true,
+                };
+                if lowest >= self.rhs[row] && below_top(highest) {
+                    *active = false;
+                    stats.redundant_rows += 1;
+                    changed = true;
+                    continue;
+                }
+                if highest < self.rhs[row] || !below_top(lowest) {
+                    stats.infeasible = true;
+                    break;
+                }
+                // Fixing a variable the row forces to 1 takes its coefficient off the
+                // right-hand side, so it comes off `highest` too to keep the two
+                // matched.  (`lowest` and `top` are both taken from before the fixing,
+                // so they stay matched while fixing for the other side.)
+                let forced = stats.forced;
+                for column in 0..num_vars {
+                    let a = self.constraints[column][row];
+                    if fixed[column].is_some() || a == T::zero() {
+                        continue;
+                    }
+                    let mut otherwise = highest;
+                    if a > T::zero() {
+                        otherwise -= &a;
+                    } else {
+                        otherwise += &a;
+                    }
+                    if otherwise < self.rhs[row] {
+                        let value = u8::from(a > T::zero());
+                        if value == 1 {
+                            highest -= &a;
+                        }
+                        self.fix_column(column, value, &mut fixed);
+                        stats.forced += 1;
+                        changed = true;
+                    }
+                }
+                let Some(top) = top else {
+                    continue;
+                };
+                if stats.forced > forced {
+                    // the other side waits for the next pass
+                    continue;
+                }
+                for column in 0..num_vars {
+                    let a = self.constraints[column][row];
+                    if fixed[column].is_some() || a == T::zero() {
+                        continue;
+                    }
+                    // the least the left-hand side can be with the other value
+                    let mut otherwise = lowest;
+                    if a > T::zero() {
+                        otherwise += &a;
+                    } else {
+                        otherwise -= &a;
+                    }
+                    if otherwise > top {
+                        let value = u8::from(a < T::zero());
+                        self.fix_column(column, value, &mut fixed);
+                        stats.forced += 1;
+                        changed = true;
+                    }
+                }
+            }
+            if stats.infeasible {
+                break;
+            }
+
+            for column in 0..num_vars {
+                if fixed[column].is_some() {
+                    continue;
+                }
+                // a row with a range may need the variable either way
+                let entries = || {
+                    self.constraints[column]
+                        .iter()
+                        .zip(&active)
+                        .zip(&self.ranges)
+                        .filter(|((a, &active), width)| {
+                            active && (width.is_none() || **a != T::zero())
+                        })
+                        .map(|((a, _), width)| (*a, width.is_none()))
+                };
+                let hurts = entries().all(|(a, plain)| plain && a <= T::zero());
+                let helps = entries().all(|(a, plain)| plain && a >= T::zero());
+                let value = if hurts {
+                    0
+                } else if helps && self.coefficients[column] == T::zero() {
+                    1
+                } else {
+                    continue;
+                };
+                self.fix_column(column, value, &mut fixed);
+                stats.dominated += 1;
+                changed = true;
+            }
+
+            match self.parallel_rows(&fixed, &mut active) {
+                Some(0) => {}
+                Some(merged) => {
+                    stats.parallel_rows += merged;
+                    changed = true;
+                }
+                None => stats.infeasible = true,
+            }
+        }
+
+        self.remove(&fixed, &active);
+        self.reset();
+        stats
+    }
+
+    /// Fix variables, given by the caller's index and value, and take them out of the
+    /// search the way `presolve` does
+    pub(crate) fn fix_variables(&mut self, values: &[(usize, u8)]) {
+        if values.is_empty() {
+            return;
+        }
+        let mut fixed = vec![None; self.coefficients.len()];
+        for &(original, value) in values {
+            if let Some(column) = self.order.iter().position(|&o| o == original) {
+                let value = self.transform.value(original, value);
+                self.fix_column(column, value, &mut fixed);
+            }
+        }
+        self.remove(&fixed, &vec![true; self.rhs.len()]);
+    }
+
+    /// Fix a column to `value`, moving what it contributes into the right-hand sides
+    /// and the objective offset
+    fn fix_column(&mut self, column: usize, value: u8, fixed: &mut [Option<u8>]) {
+        fixed[column] = Some(value);
+        if value == 1 {
+            self.transform.offset += &self.coefficients[column];
+            self.rhs
+                .iter_mut()
+                .zip(&self.constraints[column])
+                .for_each(|(b, a)| *b -= a);
+        }
+    }
+
+    /// Drop the weaker of each pair of active rows without ranges that are positive
+    /// multiples of each other (over the free columns).  Returns the number of rows dropped, or `None` if
+    /// two rows that are negative multiples of each other leave no room between them.
+    fn parallel_rows(&self, fixed: &[Option<u8>], active: &mut [bool]) -> Option<usize> {
+        let to_f64 = |value: &T| value.to_f64().unwrap_or(f64::NAN);
+        let free: Vec<usize> = (0..fixed.len()).filter(|&c| fixed[c].is_none()).collect();
+        let rows: Vec<(usize, Vec<f64>)> = (0..self.rhs.len())
+            .filter(|&row| active[row] && self.ranges[row].is_none())
+            .map(|row| {
+                let entries = free
+                    .iter()
+                    .map(|&column| to_f64(&self.constraints[column][row]))
+                    .collect();
+                (row, entries)
+            })
+            .collect();
+
+        let mut merged = 0;
+        for (i, (row, entries)) in rows.iter().enumerate() {
+            let Some(pivot) = entries.iter().position(|&a| a != 0.0) else {
+                continue;
+            };
+            for (other, other_entries) in &rows[i + 1..] {
+                if !active[*row] {
+                    break;
+                }
+                if !active[*other] || other_entries[pivot] == 0.0 {
+                    continue;
+                }
+                // other = scale * row
+                let scale = other_entries[pivot] / entries[pivot];
+                if !entries
+                    .iter()
+                    .zip(other_entries)
+                    .all(|(&a, &b)| close(scale * a, b))
+                {
+                    continue;
+                }
+                // in terms of `row`, the other row says row >= (or <=) threshold
+                let threshold = to_f64(&self.rhs[*other]) / scale;
+                let rhs = to_f64(&self.rhs[*row]);
+                if scale > 0.0 {
+                    if threshold > rhs {
+                        active[*row] = false;
+                    } else {
+                        active[*other] = false;
+                    }
+                    merged += 1;
+                } else if rhs > threshold && !close(rhs, threshold) {
+                    return None;
+                }
+            }
+        }
+        Some(merged)
+    }
+
+    /// Take the fixed columns and inactive rows out of the problem, remembering the
+    /// values of the fixed columns for `to_solution`
+    fn remove(&mut self, fixed: &[Option<u8>], active: &[bool]) {
+        let mut keep: Vec<bool> = fixed.iter().map(Option::is_none).collect();
+        if !keep.contains(&true) {
+            // the solver needs at least one column, so keep one that does nothing
+            // (its value still comes from `removed`)
+            keep[0] = true;
+            self.coefficients[0] = T::zero();
+            self.constraints[0].iter_mut().for_each(|a| *a = T::zero());
+        }
+        for (column, value) in fixed.iter().enumerate() {
+            if let Some(value) = value {
+                self.removed.push((self.order[column], *value));
+            }
+        }
+
+        let mut column = 0;
+        self.coefficients.retain(|_| {
+            column += 1;
+            keep[column - 1]
+        });
+        let mut column = 0;
+        self.constraints.retain(|_| {
+            column += 1;
+            keep[column - 1]
+        });
+        let mut column = 0;
+        self.order.retain(|_| {
+            column += 1;
+            keep[column - 1]
+        });
+        for entries in self.constraints.iter_mut() {
+            let mut row = 0;
+            entries.retain(|_| {
+                row += 1;
+                active[row - 1]
+            });
+        }
+        let mut row = 0;
+        self.rhs.retain(|_| {
+            row += 1;
+            active[row - 1]
+        });
+        let mut row = 0;
+        self.ranges.retain(|_| {
+            row += 1;
+            active[row - 1]
+        });
+        self.update_cumulative();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Balas;
+    use std::path::Path;
+
+    #[test]
+    fn fills_in_the_fixed_variables() {
+        let path = Path::new("samples/v006c016.lp");
+        let expected = Balas::from_lp(path).unwrap().solve().solution.unwrap();
+
+        let mut balas = Balas::from_lp(path).unwrap();
+        let stats = balas.presolve();
+        assert_eq!(stats.forced + stats.dominated, 6);
+        let solution = balas.solve().solution.unwrap();
+        assert_eq!(solution.objective, expected.objective);
+        assert_eq!(solution.variables, expected.variables);
+    }
+
+    #[test]
+    fn keeps_the_optimal_objective() {
+        for sample in ["demo", "parallel", "v006c032", "v006c064", "v032c032"] {
+            let path = format!("samples/{sample}.lp");
+            let mut plain = Balas::from_lp(Path::new(&path)).unwrap();
+            let mut presolved = Balas::from_lp(Path::new(&path)).unwrap();
+            presolved.presolve();
+            assert_eq!(
+                presolved.solve().solution.unwrap().objective,
+                plain.solve().solution.unwrap().objective,
+                "{sample}"
+            );
+        }
+    }
+
+    #[test]
+    fn fixed_variables_survive_serialization() {
+        let mut balas = Balas::from_lp(Path::new("samples/demo.lp")).unwrap();
+        balas.presolve();
+        let json = serde_json::to_string(&balas).unwrap();
+        let expected = balas.solve().solution.unwrap();
+
+        let mut loaded: Balas<f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.solve().solution.unwrap(), expected);
+    }
+}
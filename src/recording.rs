@@ -0,0 +1,166 @@
+use crate::lp_errors::LpErrors;
+use crate::{Balas, NodeState, Record, Transform};
+use num::ToPrimitive;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// How much of the search is recorded.  Recording every node is slow and takes a lot
+/// of memory on anything but small problems, so it is off by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RecordingLevel {
+    #[default]
+    Off,
+    /// The path, outcome, objective and a summary of the violated constraints of each
+    /// node
+    Nodes,
+    /// Everything in `Nodes`, plus the value of every constraint at each node
+    Full,
+}
+
+impl FromStr for RecordingLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" => Ok(RecordingLevel::Off),
+            "nodes" => Ok(RecordingLevel::Nodes),
+            "full" => Ok(RecordingLevel::Full),
+            _ => Err(format!(
+                "unknown recording level \"{s}\" (expected off, nodes or full)"
+            )),
+        }
+    }
+}
+
+fn to_f64<T: ToPrimitive>(value: &T) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+impl<T> Balas<T>
+where
+    T: Copy + PartialOrd + num::Zero + ToPrimitive,
+{
+    /// Count the outcome of a node, given the values of its columns, in the statistics,
+    /// and record the node if recording is on
+    #[inline]
+    pub(crate) fn record(
+        &mut self,
+        vars: &[u8],
+        objective: T,
+        accumulator: &[T],
+        state: NodeState,
+    ) {
+        match state {
+            NodeState::Fathomed => self.stats.fathomed += 1,
+            NodeState::Suboptimal => self.stats.pruned_by_bound += 1,
+            NodeState::ImpossibleChildren => self.stats.pruned_by_lookahead += 1,
+            NodeState::Infeasible => self.stats.infeasible += 1,
+            NodeState::Skipped => self.stats.skipped += 1,
+            NodeState::Default | NodeState::Active | NodeState::Visited => {}
+        }
+        self.stats.max_depth = self.stats.max_depth.max(vars.len());
+        if self.recording_level != RecordingLevel::Off {
+            self.push_record(vars, objective, accumulator, state);
+        }
+    }
+
+    /// Add a node to the recording.  A node is only recorded once: a feasible node that
+    /// the pool keeps searching below stays fathomed.  A fathomed node is the new
+    /// incumbent if it beats the best so far.
+    #[inline(never)]
+    fn push_record(&mut self, vars: &[u8], objective: T, accumulator: &[T], state: NodeState) {
+        let node: String = vars
+            .iter()
+            .map(|&value| if value == 1 { '1' } else { '0' })
+            .collect();
+        if self.recording.last().is_some_and(|last| last.node == node) {
+            return;
+        }
+        let incumbent = state == NodeState::Fathomed && objective < self.best;
+
+        let mut violated = 0;
+        let mut violation = 0.0;
+        for (row, a) in accumulator.iter().enumerate() {
+            // how far the row is below its right-hand side, or above its range
+            let excess = if *a < T::zero() {
+                -to_f64(a)
+            } else {
+                match self.widths.get(row) {
+                    Some(width) if a > width => to_f64(a) - to_f64(width),
+                    _ => 0.0,
+                }
+            };
+            if excess > 0.0 {
+                violated += 1;
+                violation += excess;
+            }
+        }
+        let accumulator = match self.recording_level {
+            RecordingLevel::Full => accumulator.iter().map(to_f64).collect(),
+            _ => vec![],
+        };
+
+        self.recording.push(Record {
+            node,
+            state,
+            incumbent,
+            objective: to_f64(&objective),
+            violated,
+            violation,
+            accumulator,
+        });
+    }
+}
+
+/// A recorded search, as read back from the `--outfile` JSON of a solve
+#[derive(Clone, Debug)]
+pub struct SearchRecording {
+    /// The name of the variable in each column, in the order they were branched on
+    pub columns: Vec<String>,
+    /// How the problem was rewritten for the solver, by the caller's index
+    pub transform: Transform<f64>,
+    /// The caller's index of the variable in each column
+    pub order: Vec<usize>,
+    /// How far each row may go above its right-hand side, as in `Balas::ranges`
+    pub ranges: Vec<Option<f64>>,
+    pub records: Vec<Record>,
+}
+
+/// The parts of a serialized solver that a `SearchRecording` needs
+#[derive(Deserialize)]
+struct Outfile {
+    vars: Vec<String>,
+    order: Vec<usize>,
+    transform: Transform<f64>,
+    #[serde(default)]
+    ranges: Vec<Option<f64>>,
+    recording: Vec<Record>,
+}
+
+impl SearchRecording {
+    /// Read the recording from the JSON that `--outfile` writes
+    pub fn read(path: &Path) -> Result<SearchRecording, LpErrors> {
+        let code = fs::read_to_string(path).map_err(LpErrors::FileReadError)?;
+        let outfile: Outfile =
+            serde_json::from_str(&code).map_err(LpErrors::RecordingParseError)?;
+        Ok(SearchRecording {
+            columns: outfile
+                .order
+                .iter()
+                .map(|&original| outfile.vars[original].clone())
+                .collect(),
+            transform: outfile.transform,
+            order: outfile.order,
+            ranges: outfile.ranges,
+            records: outfile.recording,
+        })
+    }
+
+    /// The value in the original problem of the variable in `column`, given the
+    /// solver's value
+    pub fn value(&self, column: usize, value: u8) -> u8 {
+        self.transform.value(self.order[column], value)
+    }
+}
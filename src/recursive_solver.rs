@@ -1,23 +1,36 @@
 use crate::Balas;
 use crate::NodeState;
+use crate::{NoObserver, SolveObserver, SolveResult, Status};
 use num::Bounded;
-use std::{fmt::Display, io::Write, ops::Neg};
-
+use std::{fmt::Display, io::Write, ops::ControlFlow, ops::Neg, time::Instant};
 
 impl<T> Balas<T>
 where
     T: Bounded
-        + Neg
+        + Neg<Output = T>
         + Copy
         + Display
         + num::Zero
+        + num::ToPrimitive
         + for<'a> std::ops::AddAssign<&'a T>
         + for<'a> std::ops::SubAssign<&'a T>
         + std::cmp::PartialOrd
         + std::fmt::Debug,
     Vec<T>: FromIterator<<T as Neg>::Output>,
 {
-    pub fn solve_recursively(&mut self) {
+    pub fn solve_recursively(&mut self) -> SolveResult<T> {
+        self.solve_recursively_with_observer(&mut NoObserver)
+    }
+
+    /// Solve recursively, reporting progress and new incumbents to the `observer`
+    pub fn solve_recursively_with_observer(
+        &mut self,
+        observer: &mut dyn SolveObserver<T>,
+    ) -> SolveResult<T> {
+        let start = Instant::now();
+        self.start_limits(start);
+        self.next_progress = observer.progress_interval();
+        self.prepare_rows();
         // Initialize the constraint accumulator with the negation of the b vector (the
         // right-hand side of the constraints).  This way, we can just compare against 0
         // later on.
@@ -25,15 +38,27 @@ where
         // let vars = BitVec::from_elem(self.coefficients.len(), false);
         let num_vars = self.coefficients.len();
         let vars = vec![0u8; num_vars];
-        self.record("", NodeState::Active);
-        self.record("", NodeState::Visited);
-
+        if self.zeros_feasible(&accumulator) {
+            self.solution = vars;
+            // nothing can beat this, so the search is over whatever the observer says
+            self.new_incumbent(observer);
+            return self.finish(start, None, observer);
+        }
         // self.node(0, 0, &accumulator, &T::zero(), &vars, "0".to_string());
         // self.node(1, 0, &accumulator, &T::zero(), &vars, "1".to_string());
-        self.node(0, 0, &accumulator, &T::zero(), &vars);
-        self.node(1, 0, &accumulator, &T::zero(), &vars);
+        let mut stopped = self
+            .node(0, 0, &accumulator, &T::zero(), &vars, observer)
+            .break_value();
+        if stopped.is_none() {
+            stopped = self
+                .node(1, 0, &accumulator, &T::zero(), &vars, observer)
+                .break_value();
+        }
+        self.finish(start, stopped, observer)
     }
 
+    /// Examine a node and its descendants.  Breaks with the status when the search has
+    /// to stop early.
     fn node(
         &mut self,
         branch: u8,
@@ -41,15 +66,20 @@ where
         accumulator: &[T],
         objective: &T,
         vars: &Vec<u8>,
-        // label: String,
-    ) {
-        // self.record(&label, NodeState::Active);
+        observer: &mut dyn SolveObserver<T>,
+    ) -> ControlFlow<Status> {
         let mut objective = *objective;
         let mut vars = vars.to_owned();
         let mut accumulator = accumulator.to_owned();
         // Alias the current column of the cumulative constraints
         // let ccons = &self.cumulative[index];
 
+        if let Some(status) = self
+            .check_limits()
+            .or_else(|| self.progress(index + 1, observer))
+        {
+            return ControlFlow::Break(status);
+        }
         self.count += 1;
         // println!("count:{}  branch:{branch}  index:{index}  objective:{objective}  accumulator:{accumulator:?}", self.count);
 
@@ -58,61 +88,75 @@ where
             // Alias the current column of the constraints
             let cons = &self.constraints[index];
 
+            // Update the accumulator.  This only needs to be done in the ones branch
+            accumulator.iter_mut().zip(cons).for_each(|(a, b)| *a += b);
+
             // Update the current value of the objective
             objective += &self.coefficients[index];
 
             // If we're already not better than the current best objective, then
             // we can prune this entire branch.
             if objective >= self.best {
-                // self.record(&label, NodeState::Suboptimal);
-                return;
+                self.record(
+                    &vars[..=index],
+                    objective,
+                    &accumulator,
+                    NodeState::Suboptimal,
+                );
+                return ControlFlow::Continue(());
             }
 
-            // Check if constraints satisfied, while updating the accumulator.
+            // Check if constraints satisfied.
             // We do not have to check the 0 branch, as the accumulator is not changed there.
             // If all of constraints are satisfied, then we are fathomed and we can't do any better.
-            accumulator.iter_mut().zip(cons).for_each(|(a, b)| *a += b);
-            if accumulator.iter().all(|a| *a >= T::zero()) {
+            if Self::satisfied(&accumulator, &self.widths) {
+                self.record(
+                    &vars[..=index],
+                    objective,
+                    &accumulator,
+                    NodeState::Fathomed,
+                );
                 // println!("New best objective: {} {:?}", objective, vars);
                 self.best = objective;
                 // print!("{objective} ");
                 std::io::stdout().flush().unwrap();
                 self.solution = vars;
-                // self.record(&label, NodeState::Fathomed);
-                return;
+                if let Some(status) = self.new_incumbent(observer) {
+                    return ControlFlow::Break(status);
+                }
+                return ControlFlow::Continue(());
             }
         }
-        // self.record(&label, NodeState::Visited);
         // If there is a potentially feasible descendant, then spawn 0 and 1 child nodes
-        let Some(ccons) = self.cumulative.get(index) else {
+        if index >= self.cumulative.len() {
             // println!("run out of vars with index: {index}");
-            // self.record(&label, NodeState::Infeasible);
-            return;
-        };
-
-        if accumulator
-            .iter()
-            .zip(ccons)
-            .all(|(&a, &b)| a + b >= T::zero())
-        {
-            self.node(
-                0,
-                index + 1,
+            self.record(
+                &vars[..=index],
+                objective,
                 &accumulator,
-                &objective,
-                &vars,
-                // label.clone() + "0",
+                NodeState::Infeasible,
             );
-            self.node(
-                1,
-                index + 1,
+            return ControlFlow::Continue(());
+        }
+
+        if Self::completable(
+            &accumulator,
+            &self.cumulative[index],
+            &self.negative_cumulative[index],
+            &self.widths,
+        ) {
+            self.record(&vars[..=index], objective, &accumulator, NodeState::Visited);
+            self.node(0, index + 1, &accumulator, &objective, &vars, observer)?;
+            self.node(1, index + 1, &accumulator, &objective, &vars, observer)?;
+            self.stats.backtracks += 1;
+        } else {
+            self.record(
+                &vars[..=index],
+                objective,
                 &accumulator,
-                &objective,
-                &vars,
-                // label.clone() + "1",
+                NodeState::ImpossibleChildren,
             );
-        } else {
-            // self.record(&label, NodeState::ImpossibleChildren);
         }
+        ControlFlow::Continue(())
     }
 }
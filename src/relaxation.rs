@@ -0,0 +1,204 @@
+use crate::Array;
+use num::ToPrimitive;
+
+/// Subgradient steps for the bound at the root, where it is worth getting a good one
+const ROOT_ITERATIONS: usize = 500;
+/// Subgradient steps at every other node, starting from the multipliers of the last
+const NODE_ITERATIONS: usize = 5;
+/// See `surrogate::MARGIN`
+const MARGIN: f64 = 1e-9;
+
+fn to_f64<T: ToPrimitive>(value: &T) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// A lower bound on the cost of completing a node, from the Lagrangian dual of its
+/// continuous relaxation: for any multipliers y >= 0,
+///
+///   sum_i r_i y_i + sum_j min(0, c_j - sum_i a_ij y_i)
+///
+/// is a lower bound on min c·x subject to a·x >= r, 0 <= x <= 1 (over the free
+/// columns j, with r the part of the right-hand side not yet covered), and so on any
+/// completion of the node.  The multipliers are improved by subgradient steps, and are
+/// carried over from node to node since neighbouring nodes have similar relaxations.
+/// The arithmetic is done in `f64`.
+pub(crate) struct Relaxation {
+    multipliers: Vec<f64>,
+    /// The columns that are 1 in the Lagrangian minimization
+    ones: Vec<usize>,
+    subgradient: Vec<f64>,
+    /// The multipliers that gave the root bound
+    root: Vec<f64>,
+    /// Nodes pruned by the bound
+    pub(crate) pruned: usize,
+}
+
+impl Relaxation {
+    pub(crate) fn new(num_rows: usize) -> Relaxation {
+        Relaxation {
+            multipliers: vec![0.0; num_rows],
+            ones: vec![],
+            subgradient: vec![0.0; num_rows],
+            root: vec![],
+            pruned: 0,
+        }
+    }
+
+    /// A relaxation that starts from `multipliers`, as if they had given the root bound
+    pub(crate) fn seeded(multipliers: &[f64]) -> Relaxation {
+        let mut relaxation = Relaxation::new(multipliers.len());
+        relaxation.multipliers = multipliers.to_vec();
+        relaxation.root = multipliers.to_vec();
+        relaxation
+    }
+
+    /// The multipliers that gave the root bound
+    pub(crate) fn root_multipliers(&self) -> &[f64] {
+        &self.root
+    }
+
+    /// The Lagrangian bound for the current multipliers, leaving the subgradient at them
+    /// in `subgradient`
+    fn lagrangian<T: ToPrimitive>(
+        &mut self,
+        first: usize,
+        constraints: &Array<T>,
+        coefficients: &[T],
+        accumulator: &[T],
+    ) -> f64 {
+        let mut bound = 0.0;
+        for ((y, g), a) in self
+            .multipliers
+            .iter()
+            .zip(self.subgradient.iter_mut())
+            .zip(accumulator)
+        {
+            let uncovered = -to_f64(a);
+            bound += uncovered * y;
+            *g = uncovered;
+        }
+        self.ones.clear();
+        for (j, (column, cost)) in constraints.iter().zip(coefficients).enumerate().skip(first) {
+            let reduced_cost = to_f64(cost)
+                - column
+                    .iter()
+                    .zip(&self.multipliers)
+                    .map(|(a, y)| to_f64(a) * y)
+                    .sum::<f64>();
+            if reduced_cost < 0.0 {
+                bound += reduced_cost;
+                self.ones.push(j);
+            }
+        }
+        for &j in &self.ones {
+            for (g, a) in self.subgradient.iter_mut().zip(&constraints[j]) {
+                *g -= to_f64(a);
+            }
+        }
+        bound
+    }
+
+    /// Improve the multipliers with `iterations` subgradient steps and return the best
+    /// bound found on the cost of the columns from `first` on.  `gap` is the cost that
+    /// would have to be exceeded to prune the node, if there is one; the steps stop
+    /// as soon as the bound reaches it.
+    fn bound<T: ToPrimitive>(
+        &mut self,
+        first: usize,
+        constraints: &Array<T>,
+        coefficients: &[T],
+        accumulator: &[T],
+        gap: Option<f64>,
+        iterations: usize,
+    ) -> f64 {
+        let mut best = f64::NEG_INFINITY;
+        let mut step_scale = 1.0;
+        let mut since_improvement = 0;
+        for _ in 0..iterations {
+            let bound = self.lagrangian(first, constraints, coefficients, accumulator);
+            if bound > best {
+                best = bound;
+                since_improvement = 0;
+            } else {
+                since_improvement += 1;
+                if since_improvement >= 10 {
+                    step_scale /= 2.0;
+                    since_improvement = 0;
+                }
+            }
+            if gap.is_some_and(|gap| best >= gap) {
+                break;
+            }
+            // Polyak's step towards the gap (or towards a guess when there is none)
+            let norm: f64 = self
+                .multipliers
+                .iter()
+                .zip(&self.subgradient)
+                .map(|(&y, &g)| if y > 0.0 || g > 0.0 { g * g } else { 0.0 })
+                .sum();
+            if norm == 0.0 {
+                // the relaxed solution is feasible, so the bound is exact
+                break;
+            }
+            let target = match gap {
+                Some(gap) if gap.is_finite() => gap,
+                _ => best.abs() * 1.1 + 1.0,
+            };
+            let step = step_scale * (target - bound).max(MARGIN) / norm;
+            for (y, g) in self.multipliers.iter_mut().zip(&self.subgradient) {
+                *y = (*y + step * g).max(0.0);
+            }
+        }
+        best
+    }
+
+    /// A bound on the cost of solving the whole problem.  The steps head for the cost
+    /// of the `incumbent`, if there is one, and otherwise for a guess.
+    pub(crate) fn root_bound<T: ToPrimitive>(
+        &mut self,
+        constraints: &Array<T>,
+        coefficients: &[T],
+        accumulator: &[T],
+        incumbent: Option<f64>,
+    ) -> f64 {
+        if !self.root.is_empty() {
+            self.multipliers.clone_from(&self.root);
+        }
+        let bound = self.bound(
+            0,
+            constraints,
+            coefficients,
+            accumulator,
+            incumbent,
+            ROOT_ITERATIONS,
+        );
+        self.root.clone_from(&self.multipliers);
+        bound
+    }
+
+    /// Whether the node at `index` can be pruned: no completion of it can cost less
+    /// than `gap` (the cost that a completion must stay under to beat the incumbent)
+    pub(crate) fn prunes<T: ToPrimitive>(
+        &mut self,
+        index: usize,
+        constraints: &Array<T>,
+        coefficients: &[T],
+        accumulator: &[T],
+        gap: f64,
+    ) -> bool {
+        let limit = gap + MARGIN * gap.abs().max(1.0);
+        let bound = self.bound(
+            index + 1,
+            constraints,
+            coefficients,
+            accumulator,
+            Some(limit),
+            NODE_ITERATIONS,
+        );
+        if bound >= limit {
+            self.pruned += 1;
+            return true;
+        }
+        false
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{NodeState, SearchRecording};
+use std::io::{BufRead, Write};
+
+/// Steps through a recorded search one node at a time, in the order the solver
+/// examined them
+pub struct Replay<'a> {
+    recording: &'a SearchRecording,
+    step: usize,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(recording: &'a SearchRecording) -> Replay<'a> {
+        Replay { recording, step: 0 }
+    }
+
+    /// The index of the current record
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Move to the next record, unless this is the last
+    pub fn forward(&mut self) -> bool {
+        let moved = self.step + 1 < self.recording.records.len();
+        if moved {
+            self.step += 1;
+        }
+        moved
+    }
+
+    /// Move to the previous record, unless this is the first
+    pub fn back(&mut self) -> bool {
+        let moved = self.step > 0;
+        if moved {
+            self.step -= 1;
+        }
+        moved
+    }
+
+    /// Move to a record, or as close to it as there is
+    pub fn go_to(&mut self, step: usize) {
+        self.step = step.min(self.recording.records.len().saturating_sub(1));
+    }
+
+    /// The objective of the best solution found up to the current record, in terms of
+    /// the original problem
+    fn best(&self) -> Option<f64> {
+        self.recording.records[..=self.step]
+            .iter()
+            .rev()
+            .find(|record| record.incumbent)
+            .map(|record| self.recording.transform.objective(record.objective))
+    }
+
+    /// Why the search did what it did at the current record
+    fn reason(&self) -> String {
+        let record = &self.recording.records[self.step];
+        let depth = record.node.len();
+        match record.state {
+            NodeState::Visited => match self.recording.columns.get(depth) {
+                Some(next) => format!("some completion may still be feasible, so branch on {next}"),
+                None => "searched".to_string(),
+            },
+            NodeState::Fathomed if record.incumbent => {
+                "feasible and better than the best so far: the new incumbent".to_string()
+            }
+            NodeState::Fathomed => {
+                "feasible, so setting more variables to 1 can only cost more".to_string()
+            }
+            NodeState::Infeasible => {
+                "pruned as infeasible: every variable is set and a constraint is violated"
+                    .to_string()
+            }
+            NodeState::Suboptimal => {
+                "pruned by the bound: its objective is no better than the best so far".to_string()
+            }
+            NodeState::ImpossibleChildren => {
+                "pruned by the look-ahead: no setting of the remaining variables satisfies every \
+                 constraint"
+                    .to_string()
+            }
+            NodeState::Skipped => {
+                "pruned by the must-set rule, the surrogate constraint or the relaxation bound"
+                    .to_string()
+            }
+            NodeState::Default | NodeState::Active => format!("{:?}", record.state),
+        }
+    }
+
+    /// Show the current record: the partial assignment, the objective, the best so far,
+    /// the value of each constraint (if they were recorded) and what became of the node
+    pub fn show(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let recording = self.recording;
+        let Some(record) = recording.records.get(self.step) else {
+            return writeln!(
+                out,
+                "The recording is empty (solve with --record, and without --threads)"
+            );
+        };
+        writeln!(
+            out,
+            "Step {} of {}: node {} at depth {}",
+            self.step + 1,
+            recording.records.len(),
+            if record.node.is_empty() {
+                "root"
+            } else {
+                &record.node
+            },
+            record.node.len()
+        )?;
+        let assignment: Vec<_> = record
+            .node
+            .chars()
+            .enumerate()
+            .map(|(column, value)| {
+                let value = recording.value(column, u8::from(value == '1'));
+                format!("{} = {value}", recording.columns[column])
+            })
+            .collect();
+        if assignment.is_empty() {
+            writeln!(out, "  Assignment: nothing set")?;
+        } else {
+            writeln!(out, "  Assignment: {}", assignment.join(", "))?;
+        }
+        let objective = recording.transform.objective(record.objective);
+        match self.best() {
+            Some(best) => writeln!(out, "  Objective: {objective}  best so far: {best}")?,
+            None => writeln!(out, "  Objective: {objective}  best so far: none")?,
+        }
+        match record.violated {
+            0 => writeln!(out, "  Violated constraints: none")?,
+            violated => writeln!(
+                out,
+                "  Violated constraints: {violated} (by {} in all)",
+                record.violation
+            )?,
+        }
+        if record.accumulator.is_empty() {
+            writeln!(out, "    (solve with --record full to see each constraint)")?;
+        }
+        for (row, &value) in record.accumulator.iter().enumerate() {
+            let width = recording.ranges.get(row).copied().flatten();
+            let state = match width {
+                _ if value < 0.0 => "below its right-hand side",
+                Some(width) if value > width => "above its range",
+                _ => "satisfied",
+            };
+            writeln!(out, "    row {row}: lhs - rhs = {value}  {state}")?;
+        }
+        writeln!(out, "  Outcome: {:?}, {}", record.state, self.reason())
+    }
+}
+
+/// Replay a recorded search interactively, reading commands from `input`: Enter or `n`
+/// for the next node, `b` for the previous one, a step number to jump to it, and `q` to
+/// quit
+pub fn replay(
+    recording: &SearchRecording,
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    let mut replay = Replay::new(recording);
+    replay.show(out)?;
+    loop {
+        write!(out, "[n]ext, [b]ack, step number or [q]uit> ")?;
+        out.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return writeln!(out);
+        }
+        let command = line.trim();
+        match command {
+            "" | "n" | "next" => {
+                if !replay.forward() {
+                    writeln!(out, "That was the last node")?;
+                    continue;
+                }
+            }
+            "b" | "back" => {
+                if !replay.back() {
+                    writeln!(out, "That was the first node")?;
+                    continue;
+                }
+            }
+            "q" | "quit" => return Ok(()),
+            _ => match command.parse::<usize>() {
+                Ok(step) => replay.go_to(step.saturating_sub(1)),
+                Err(_) => {
+                    writeln!(out, "Unknown command \"{command}\"")?;
+                    continue;
+                }
+            },
+        }
+        replay.show(out)?;
+    }
+}
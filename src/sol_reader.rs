@@ -0,0 +1,122 @@
+use crate::lp_errors::LpErrors;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// The contents of a solution file, with the values as written
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SolutionValues {
+    /// The objective value given in the header, if there is one
+    pub objective: Option<f64>,
+    pub values: HashMap<String, f64>,
+}
+
+/// The parts of a solution written by `write_json` that are read back
+#[derive(Deserialize)]
+struct JsonSolution {
+    objective: Option<f64>,
+    variables: HashMap<String, f64>,
+}
+
+/// Read the variable values from a solution file.  Each line holds a variable name
+/// followed by its value, and anything after the value is ignored.  Blank lines,
+/// `#` comments and the header lines that some solvers write are skipped, so this
+/// reads most `.sol` files.  The JSON written by `write_json` is read as well.
+pub fn read_assignment(sol_path: &Path) -> Result<HashMap<String, u8>, LpErrors> {
+    let code = fs::read_to_string(sol_path).map_err(LpErrors::FileReadError)?;
+    parse_assignment(&code)
+}
+
+/// Read a solution file without requiring the values to be binary, along with the
+/// objective value from its header (`# Objective value = ...` or `objective value: ...`)
+pub fn read_values(sol_path: &Path) -> Result<SolutionValues, LpErrors> {
+    let code = fs::read_to_string(sol_path).map_err(LpErrors::FileReadError)?;
+    parse_values(&code).map(|(solution, _)| solution)
+}
+
+fn parse_assignment(code: &str) -> Result<HashMap<String, u8>, LpErrors> {
+    let (solution, lines) = parse_values(code)?;
+    let mut assignment = HashMap::new();
+    for (name, value) in solution.values {
+        // allow for a little round-off from other solvers
+        let value = if value.abs() < 1e-6 {
+            0
+        } else if (value - 1.0).abs() < 1e-6 {
+            1
+        } else {
+            return Err(LpErrors::SolutionParseError {
+                line: lines[&name],
+                reason: format!("\"{name}\" is not binary"),
+            });
+        };
+        assignment.insert(name, value);
+    }
+    Ok(assignment)
+}
+
+/// Parse the solution, also returning the line that each value was found on
+fn parse_values(code: &str) -> Result<(SolutionValues, HashMap<String, usize>), LpErrors> {
+    if code.trim_start().starts_with('{') {
+        return parse_json(code);
+    }
+    let mut solution = SolutionValues::default();
+    let mut lines = HashMap::new();
+    for (i, line) in code.lines().enumerate() {
+        let line = line.trim();
+        let parse_error = |reason: String| LpErrors::SolutionParseError {
+            line: i + 1,
+            reason,
+        };
+        let header = line
+            .strip_prefix('#')
+            .unwrap_or(line)
+            .trim_start()
+            .to_lowercase();
+        if let Some(value) = header.strip_prefix("objective value") {
+            let value = value
+                .trim_start_matches([' ', '=', ':'])
+                .split_whitespace()
+                .next()
+                .unwrap_or_default();
+            solution.objective = Some(
+                value
+                    .parse()
+                    .map_err(|_| parse_error(format!("bad objective value \"{value}\"")))?,
+            );
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') || header.starts_with("solution status:") {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(value)) = (fields.next(), fields.next()) else {
+            return Err(parse_error("expected a variable name and a value".into()));
+        };
+        let value: f64 = value
+            .parse()
+            .map_err(|_| parse_error(format!("expected a number, found \"{value}\"")))?;
+        solution.values.insert(name.to_string(), value);
+        lines.insert(name.to_string(), i + 1);
+    }
+    Ok((solution, lines))
+}
+
+/// Parse a solution written by `write_json`, which is all on one line
+fn parse_json(code: &str) -> Result<(SolutionValues, HashMap<String, usize>), LpErrors> {
+    let json: JsonSolution =
+        serde_json::from_str(code).map_err(|error| LpErrors::SolutionParseError {
+            line: error.line(),
+            reason: error.to_string(),
+        })?;
+    let lines = json
+        .variables
+        .keys()
+        .map(|name| (name.clone(), 1))
+        .collect();
+    let solution = SolutionValues {
+        objective: json.objective,
+        values: json.variables,
+    };
+    Ok((solution, lines))
+}
@@ -0,0 +1,91 @@
+use crate::lp_errors::LpErrors;
+use crate::{SearchStats, SolveResult, Status};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// The file formats a solution can be written in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolutionFormat {
+    /// The common `.sol` text format: a comment with the objective value, followed by
+    /// one `name value` line per variable
+    Sol,
+    /// A single JSON object with the status, objective value and assignment
+    Json,
+}
+
+impl FromStr for SolutionFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sol" => Ok(SolutionFormat::Sol),
+            "json" => Ok(SolutionFormat::Json),
+            _ => Err(format!(
+                "unknown solution format \"{s}\" (expected sol or json)"
+            )),
+        }
+    }
+}
+
+/// The JSON form of a result
+#[derive(Serialize)]
+struct SolutionRecord<'a, T> {
+    status: &'a Status,
+    objective: Option<&'a T>,
+    variables: BTreeMap<&'a str, u8>,
+    stats: &'a SearchStats,
+}
+
+/// Write the solution in `result` to a file.  If there is no solution, the file
+/// still records the status.
+pub fn write_solution<T: Display + Serialize>(
+    result: &SolveResult<T>,
+    sol_path: &Path,
+    format: SolutionFormat,
+) -> Result<(), LpErrors> {
+    let file = File::create(sol_path).map_err(LpErrors::FileWriteError)?;
+    let mut out = BufWriter::new(file);
+    match format {
+        SolutionFormat::Sol => write_sol(result, &mut out),
+        SolutionFormat::Json => write_json(result, &mut out),
+    }
+    .and_then(|()| out.flush())
+    .map_err(LpErrors::FileWriteError)
+}
+
+/// Write the solution in the `.sol` text format, which `read_assignment` reads back
+pub fn write_sol<T: Display>(result: &SolveResult<T>, out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(out, "# Solution status: {:?}", result.status)?;
+    if let Some(solution) = &result.solution {
+        writeln!(out, "# Objective value = {}", solution.objective)?;
+        for (var, value) in &solution.variables {
+            writeln!(out, "{var} {value}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Write the solution as a compact JSON object, along with the search statistics
+pub fn write_json<T: Serialize>(
+    result: &SolveResult<T>,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    let record = SolutionRecord {
+        status: &result.status,
+        objective: result.solution.as_ref().map(|solution| &solution.objective),
+        variables: result
+            .solution
+            .iter()
+            .flat_map(|solution| &solution.variables)
+            .map(|(var, value)| (var.as_str(), *value))
+            .collect(),
+        stats: &result.stats,
+    };
+    serde_json::to_writer(&mut *out, &record)?;
+    writeln!(out)
+}
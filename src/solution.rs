@@ -0,0 +1,156 @@
+use crate::{Limit, SearchStats};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::ops::{AddAssign, SubAssign};
+use std::time::Duration;
+
+/// Records how a problem was rewritten into the form required by the Balas
+/// algorithm (minimize, with nonnegative objective coefficients), so that results
+/// can be mapped back to the original problem.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Transform<T> {
+    /// For each column, whether the variable was complemented (x -> 1 - x)
+    pub complemented: Vec<bool>,
+    /// Whether the objective was negated to turn a maximization into a minimization
+    pub negated: bool,
+    /// Constant that was dropped from the (minimization) objective
+    pub offset: T,
+}
+
+impl<T> Transform<T>
+where
+    T: Copy + num::Zero + for<'a> AddAssign<&'a T> + for<'a> SubAssign<&'a T>,
+{
+    /// A transform that leaves the problem unchanged
+    pub fn identity(num_vars: usize) -> Transform<T> {
+        Transform {
+            complemented: vec![false; num_vars],
+            negated: false,
+            offset: T::zero(),
+        }
+    }
+
+    /// Add a constant term from the original objective
+    pub fn add_constant(&mut self, constant: T) {
+        if self.negated {
+            self.offset -= &constant;
+        } else {
+            self.offset += &constant;
+        }
+    }
+
+    /// The value of the original variable, given the value of a column in the solver
+    pub fn value(&self, column: usize, value: u8) -> u8 {
+        if self.complemented[column] {
+            1 - value
+        } else {
+            value
+        }
+    }
+
+    /// The value of the solver's objective, given the value of the original objective
+    pub fn solver_objective(&self, objective: T) -> T {
+        let mut value = if self.negated {
+            let mut negated = T::zero();
+            negated -= &objective;
+            negated
+        } else {
+            objective
+        };
+        value -= &self.offset;
+        value
+    }
+
+    /// The value of the original objective, given the value of the solver's objective
+    pub fn objective(&self, objective: T) -> T {
+        let mut objective = objective;
+        objective += &self.offset;
+        if self.negated {
+            // subtract from zero rather than negate, which would give -0 for floats
+            let mut negated = T::zero();
+            negated -= &objective;
+            negated
+        } else {
+            objective
+        }
+    }
+}
+
+/// A solution expressed in terms of the original problem
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Solution<T> {
+    pub objective: T,
+    pub variables: Vec<(String, u8)>,
+}
+
+/// How a solve ended
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum Status {
+    /// No solve has been run yet
+    Unsolved,
+    /// The search completed, so the solution is optimal
+    Optimal,
+    /// The search completed without finding a solution better than the initial bound
+    Infeasible,
+    /// The search was stopped early; the solution (if any) is the best found so far
+    Interrupted,
+    /// The search stopped at one of the solver limits; the solution (if any) is the
+    /// best found so far
+    LimitReached(Limit),
+}
+
+/// The outcome of a solve, in terms of the original problem
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SolveResult<T> {
+    pub status: Status,
+    pub solution: Option<Solution<T>>,
+    pub nodes: usize,
+    pub elapsed: Duration,
+    /// The statistics of the search
+    #[serde(default)]
+    pub stats: SearchStats,
+    /// A bound on the original objective from the relaxation at the root, when bounding
+    /// with the relaxation (a lower bound when minimizing, an upper one when maximizing)
+    pub bound: Option<f64>,
+}
+
+impl<T> SolveResult<T>
+where
+    T: num::ToPrimitive,
+{
+    /// The relative distance between the solution and the bound, if there are both
+    pub fn gap(&self) -> Option<f64> {
+        let objective = self.solution.as_ref()?.objective.to_f64()?;
+        let bound = self.bound?;
+        Some(((objective - bound) / objective.abs().max(1e-10)).abs())
+    }
+}
+
+impl<T: Display + num::ToPrimitive> Display for SolveResult<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.status, &self.solution) {
+            (_, None) => writeln!(f, "No solution ({:?})", self.status)?,
+            (Status::Optimal, Some(solution)) => {
+                writeln!(f, "Optimal value: {}", solution.objective)?
+            }
+            (status, Some(solution)) => {
+                writeln!(f, "Best value found: {} ({status:?})", solution.objective)?
+            }
+        }
+        match (self.bound, self.gap()) {
+            (Some(bound), Some(gap)) => {
+                writeln!(f, "Root bound: {bound} (gap: {:.2}%)", 100.0 * gap)?
+            }
+            (Some(bound), None) => writeln!(f, "Root bound: {bound}")?,
+            _ => {}
+        }
+        if let Some(solution) = &self.solution {
+            writeln!(f, "Solution:")?;
+            for (var, value) in &solution.variables {
+                writeln!(f, "  {var}: {value}")?;
+            }
+        }
+        writeln!(f, "Examined {} nodes in {:?}", self.nodes, self.elapsed)?;
+        write!(f, "{}", self.stats)
+    }
+}
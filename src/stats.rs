@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::time::Duration;
+
+/// A new incumbent found during a search
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Improvement {
+    /// How many nodes had been examined when it was found
+    pub node: usize,
+    /// The time since the search started
+    pub elapsed: Duration,
+    /// Its objective in the original problem
+    pub objective: f64,
+}
+
+/// What happened during the last search.  The outcome counts are of the nodes where a
+/// branch of the search ended; the children of every other node were searched.  The
+/// exception is a solution pool, which also searches below fathomed nodes for more
+/// solutions.  A parallel search counts a subproblem that the bound rules out before
+/// it starts as one node pruned by the bound, at the depth of the split.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SearchStats {
+    /// Feasible nodes, which none of their descendants can improve on
+    pub fathomed: usize,
+    /// Nodes whose objective was already no better than the incumbent
+    pub pruned_by_bound: usize,
+    /// Nodes that no setting of the remaining variables could make feasible
+    pub pruned_by_lookahead: usize,
+    /// Nodes with every variable set that still violate a constraint
+    pub infeasible: usize,
+    /// Nodes pruned by the must-set rule, the surrogate constraint or the relaxation
+    /// bound
+    pub skipped: usize,
+    /// The most variables set at any node
+    pub max_depth: usize,
+    /// How many times the search went back up the tree
+    pub backtracks: usize,
+    /// Each new incumbent, in the order they were found
+    pub improvements: Vec<Improvement>,
+    /// The time it took to find the first feasible solution
+    pub first_feasible: Option<Duration>,
+}
+
+impl SearchStats {
+    /// Note a new incumbent
+    pub(crate) fn improved(&mut self, node: usize, elapsed: Duration, objective: f64) {
+        self.first_feasible.get_or_insert(elapsed);
+        self.improvements.push(Improvement {
+            node,
+            elapsed,
+            objective,
+        });
+    }
+
+    /// Add the statistics of a subproblem whose first `depth` variables were fixed, and
+    /// whose search started `started` into this one.  The incumbents of the subproblems
+    /// are merged in time order, keeping those that improved on all of the earlier ones.
+    pub(crate) fn merge(
+        &mut self,
+        sub: &SearchStats,
+        depth: usize,
+        started: Duration,
+        negated: bool,
+    ) {
+        self.fathomed += sub.fathomed;
+        self.pruned_by_bound += sub.pruned_by_bound;
+        self.pruned_by_lookahead += sub.pruned_by_lookahead;
+        self.infeasible += sub.infeasible;
+        self.skipped += sub.skipped;
+        self.max_depth = self.max_depth.max(sub.max_depth + depth);
+        self.backtracks += sub.backtracks;
+
+        let mut improvements = std::mem::take(&mut self.improvements);
+        improvements.extend(sub.improvements.iter().map(|improvement| Improvement {
+            elapsed: improvement.elapsed + started,
+            ..improvement.clone()
+        }));
+        improvements.sort_by_key(|improvement| improvement.elapsed);
+        self.first_feasible = None;
+        for improvement in improvements {
+            let better = match self.improvements.last() {
+                Some(last) if negated => improvement.objective > last.objective,
+                Some(last) => improvement.objective < last.objective,
+                None => true,
+            };
+            if better {
+                self.improved(improvement.node, improvement.elapsed, improvement.objective);
+            }
+        }
+    }
+}
+
+impl Display for SearchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Ended branches: {} fathomed, {} pruned by the bound, ",
+            self.fathomed, self.pruned_by_bound
+        )?;
+        writeln!(
+            f,
+            "{} by the look-ahead, {} infeasible, {} skipped",
+            self.pruned_by_lookahead, self.infeasible, self.skipped
+        )?;
+        write!(
+            f,
+            "Maximum depth: {}, backtracks: {}",
+            self.max_depth, self.backtracks
+        )?;
+        if let Some(first) = self.first_feasible {
+            write!(
+                f,
+                "\nIncumbents: {} (the first after {first:?})",
+                self.improvements.len()
+            )?;
+            for improvement in &self.improvements {
+                write!(
+                    f,
+                    "\n  {} at node {} after {:?}",
+                    improvement.objective, improvement.node, improvement.elapsed
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
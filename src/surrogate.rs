@@ -0,0 +1,104 @@
+use crate::Array;
+use num::ToPrimitive;
+
+/// Surrogate constraints (Balas, Glover).  At each node, the violated constraints are
+/// added up, each weighted by how much it is violated, into a single constraint over
+/// the free variables.  Any completion of the node has to satisfy it, so the node can
+/// be pruned if the surrogate cannot be satisfied at all, or if the cheapest way to
+/// satisfy its linear relaxation (a fractional knapsack, solved greedily) already
+/// costs too much to beat the incumbent.  The arithmetic is done in `f64`.
+pub(crate) struct Surrogate {
+    /// (row, weight) of the violated rows
+    weights: Vec<(usize, f64)>,
+    /// (cost, surrogate coefficient) of the free columns that help the surrogate
+    items: Vec<(f64, f64)>,
+    /// Nodes pruned by the surrogate
+    pub(crate) pruned: usize,
+}
+
+/// Rounding errors must not prune a node that could hold a better solution, so a bound
+/// has to exceed what it is compared with by this much (relative to its size)
+const MARGIN: f64 = 1e-9;
+
+fn to_f64<T: ToPrimitive>(value: &T) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+impl Surrogate {
+    pub(crate) fn new() -> Surrogate {
+        Surrogate {
+            weights: vec![],
+            items: vec![],
+            pruned: 0,
+        }
+    }
+
+    /// Whether the node at `index`, with this `accumulator`, can be pruned.  `gap` is
+    /// how much the free columns may add to the objective while still beating the
+    /// incumbent, if there is one to beat.
+    pub(crate) fn prunes<T: ToPrimitive>(
+        &mut self,
+        index: usize,
+        constraints: &Array<T>,
+        coefficients: &[T],
+        accumulator: &[T],
+        gap: Option<f64>,
+    ) -> bool {
+        self.weights.clear();
+        self.weights.extend(
+            accumulator
+                .iter()
+                .enumerate()
+                .map(|(i, a)| (i, -to_f64(a)))
+                .filter(|&(_, violation)| violation > 0.0),
+        );
+        if self.weights.is_empty() {
+            return false;
+        }
+        // the surrogate of the rows `a·x >= -accumulator` weighted by their violations
+        let required: f64 = self.weights.iter().map(|(_, u)| u * u).sum();
+
+        self.items.clear();
+        let mut available = 0.0;
+        for (column, cost) in constraints.iter().zip(coefficients).skip(index + 1) {
+            let s: f64 = self
+                .weights
+                .iter()
+                .map(|&(i, u)| u * to_f64(&column[i]))
+                .sum();
+            if s > 0.0 {
+                available += s;
+                self.items.push((to_f64(cost), s));
+            }
+        }
+        if available < required * (1.0 - MARGIN) {
+            self.pruned += 1;
+            return true;
+        }
+
+        let Some(gap) = gap else {
+            return false;
+        };
+        let limit = gap + MARGIN * gap.abs().max(1.0);
+        self.items
+            .sort_by(|(c1, s1), (c2, s2)| (c1 / s1).total_cmp(&(c2 / s2)));
+        let mut needed = required;
+        let mut bound = 0.0;
+        for &(c, s) in &self.items {
+            if s >= needed {
+                bound += c * needed / s;
+                break;
+            }
+            bound += c;
+            needed -= s;
+            if bound >= limit {
+                break;
+            }
+        }
+        if bound >= limit {
+            self.pruned += 1;
+            return true;
+        }
+        false
+    }
+}
@@ -0,0 +1,330 @@
+use crate::extras::{split_lp_extras, Extras};
+use crate::lp_errors::LpErrors;
+use crate::mps_reader::{parse_mps, MpsFormat};
+use crate::{Solution, SolutionValues};
+use lp_parser_rs::model::constraint::Constraint;
+use lp_parser_rs::model::lp_problem::LPProblem;
+use lp_parser_rs::model::sense::Cmp;
+use lp_parser_rs::parse::parse_lp_file;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+/// Constraints may be violated (and values may differ from 0 and 1) by this much
+const TOLERANCE: f64 = 1e-6;
+
+/// A problem exactly as it was read, before any of the rewriting done for the solver.
+/// Checking solutions against it does not depend on the solver's normalization.
+pub struct Model {
+    lp: LPProblem,
+    extras: Extras,
+}
+
+/// How well a solution fits one of the original constraints
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstraintCheck {
+    pub name: String,
+    /// The value of the left-hand side
+    pub activity: f64,
+    pub sense: Cmp,
+    pub rhs: f64,
+    /// The upper side of a range, whose lower side is `rhs`
+    pub upper: Option<f64>,
+    /// How far the constraint is from being violated.  This is negative when it is
+    /// violated, and for an equality it is never positive.
+    pub slack: f64,
+}
+
+impl ConstraintCheck {
+    pub fn satisfied(&self) -> bool {
+        self.slack >= -TOLERANCE
+    }
+}
+
+/// The result of checking a solution against a model
+#[derive(Clone, Debug, PartialEq)]
+pub struct Verification {
+    /// The objective value of the solution, computed from the model
+    pub objective: f64,
+    /// The objective value that came with the solution, if any
+    pub claimed_objective: Option<f64>,
+    /// Every constraint, in order of name
+    pub constraints: Vec<ConstraintCheck>,
+    /// Variables whose value is not 0 or 1
+    pub not_binary: Vec<(String, f64)>,
+    /// Names in the solution that are not variables of the model
+    pub unknown: Vec<String>,
+}
+
+impl Verification {
+    pub fn violations(&self) -> impl Iterator<Item = &ConstraintCheck> {
+        self.constraints.iter().filter(|check| !check.satisfied())
+    }
+
+    /// Whether the claimed objective value (if any) matches the computed one
+    pub fn objective_matches(&self) -> bool {
+        match self.claimed_objective {
+            Some(claimed) => (claimed - self.objective).abs() <= TOLERANCE * claimed.abs().max(1.0),
+            None => true,
+        }
+    }
+
+    /// Whether the solution is a feasible binary solution with the objective it claims
+    pub fn is_valid(&self) -> bool {
+        self.violations().next().is_none()
+            && self.not_binary.is_empty()
+            && self.unknown.is_empty()
+            && self.objective_matches()
+    }
+}
+
+impl Model {
+    pub(crate) fn new(lp: LPProblem, extras: Extras) -> Model {
+        Model { lp, extras }
+    }
+
+    pub fn from_lp(lp_path: &Path) -> Result<Model, LpErrors> {
+        let code = fs::read_to_string(lp_path).map_err(LpErrors::FileReadError)?;
+        let (code, extras) = split_lp_extras(&code)?;
+        let lp = parse_lp_file(&code).map_err(LpErrors::LPParseError)?;
+        Ok(Model::new(lp, extras))
+    }
+
+    pub fn from_mps(mps_path: &Path) -> Result<Model, LpErrors> {
+        Self::read_mps(mps_path, MpsFormat::Free)
+    }
+
+    pub fn from_fixed_mps(mps_path: &Path) -> Result<Model, LpErrors> {
+        Self::read_mps(mps_path, MpsFormat::Fixed)
+    }
+
+    fn read_mps(mps_path: &Path, format: MpsFormat) -> Result<Model, LpErrors> {
+        let code = fs::read_to_string(mps_path).map_err(LpErrors::FileReadError)?;
+        let (lp, extras) = parse_mps(&code, format)?;
+        Ok(Model::new(lp, extras))
+    }
+
+    /// Check a solution read from a file (see `read_values`)
+    pub fn verify(&self, solution: &SolutionValues) -> Result<Verification, LpErrors> {
+        let mut verification = self.check(&solution.values)?;
+        verification.claimed_objective = solution.objective;
+        Ok(verification)
+    }
+
+    /// Check a solution produced by the solver
+    pub fn verify_solution(&self, solution: &Solution<f64>) -> Result<Verification, LpErrors> {
+        let values = solution
+            .variables
+            .iter()
+            .map(|(var, value)| (var.clone(), f64::from(*value)))
+            .collect();
+        let mut verification = self.check(&values)?;
+        verification.claimed_objective = Some(solution.objective);
+        Ok(verification)
+    }
+
+    fn check(&self, values: &HashMap<String, f64>) -> Result<Verification, LpErrors> {
+        // the variables of the model, including any that only appear in the constraints
+        let mut variables: Vec<&String> = self
+            .lp
+            .variables
+            .keys()
+            .chain(
+                self.lp
+                    .objectives
+                    .iter()
+                    .flat_map(|o| &o.coefficients)
+                    .map(|c| &c.var_name),
+            )
+            .chain(
+                self.lp
+                    .constraints
+                    .values()
+                    .flat_map(|constraint| match constraint {
+                        Constraint::Standard { coefficients, .. } => coefficients.iter(),
+                        _ => [].iter(),
+                    })
+                    .map(|c| &c.var_name),
+            )
+            .collect();
+        variables.sort();
+        variables.dedup();
+        if let Some(var) = variables.iter().find(|var| !values.contains_key(**var)) {
+            return Err(LpErrors::MissingValue(var.to_string()));
+        }
+        // a variable that only the bounds mention is taken to be 0
+        let value = |var: &str| values.get(var).copied().unwrap_or(0.0);
+
+        let objective = self.lp.objectives.first().ok_or(LpErrors::NoObjective)?;
+        let objective = objective
+            .coefficients
+            .iter()
+            .map(|c| c.coefficient * value(&c.var_name))
+            .sum::<f64>()
+            + self.extras.objective_constant;
+
+        let mut constraints = vec![];
+        for constraint in self.lp.constraints.values() {
+            let Constraint::Standard {
+                name,
+                coefficients,
+                sense,
+                rhs,
+            } = constraint
+            else {
+                return Err(LpErrors::UnexpectedConstraintType);
+            };
+            let activity: f64 = coefficients
+                .iter()
+                .map(|c| c.coefficient * value(&c.var_name))
+                .sum();
+            let upper = self.extras.ranges.get(name).copied();
+            let slack = match (sense, upper) {
+                (_, Some(upper)) => (activity - rhs).min(upper - activity),
+                (Cmp::GreaterOrEqual | Cmp::GreaterThan, None) => activity - rhs,
+                (Cmp::LessOrEqual | Cmp::LessThan, None) => rhs - activity,
+                (Cmp::Equal, None) => 0.0 - (activity - rhs).abs(),
+            };
+            constraints.push(ConstraintCheck {
+                name: name.clone(),
+                activity,
+                sense: sense.clone(),
+                rhs: *rhs,
+                upper,
+                slack,
+            });
+        }
+        // a variable fixed by its bounds is checked like the constraint `var = value`
+        for (var, &fixed) in &self.extras.fixed {
+            let activity = value(var);
+            constraints.push(ConstraintCheck {
+                name: format!("{var}_fixed"),
+                activity,
+                sense: Cmp::Equal,
+                rhs: f64::from(fixed),
+                upper: None,
+                slack: 0.0 - (activity - f64::from(fixed)).abs(),
+            });
+        }
+        constraints.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut not_binary: Vec<(String, f64)> = values
+            .iter()
+            .filter(|(_, &v)| v.abs() > TOLERANCE && (v - 1.0).abs() > TOLERANCE)
+            .map(|(var, &v)| (var.clone(), v))
+            .collect();
+        not_binary.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut unknown: Vec<String> = values
+            .keys()
+            .filter(|var| variables.binary_search(var).is_err())
+            .cloned()
+            .collect();
+        unknown.sort();
+
+        Ok(Verification {
+            objective,
+            claimed_objective: None,
+            constraints,
+            not_binary,
+            unknown,
+        })
+    }
+}
+
+impl Display for Verification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.claimed_objective {
+            Some(claimed) if !self.objective_matches() => writeln!(
+                f,
+                "Objective value: {} (MISMATCH: the solution claims {claimed})",
+                self.objective
+            )?,
+            _ => writeln!(f, "Objective value: {}", self.objective)?,
+        }
+        writeln!(f, "Constraints:")?;
+        for check in &self.constraints {
+            let sense = match check.sense {
+                Cmp::GreaterOrEqual => ">=",
+                Cmp::GreaterThan => ">",
+                Cmp::LessOrEqual => "<=",
+                Cmp::LessThan => "<",
+                Cmp::Equal => "=",
+            };
+            let state = if check.satisfied() {
+                format!("slack {}", check.slack)
+            } else {
+                format!("VIOLATED by {}", -check.slack)
+            };
+            match check.upper {
+                Some(upper) => writeln!(
+                    f,
+                    "  {}: {} <= {} <= {upper}  {state}",
+                    check.name, check.rhs, check.activity
+                )?,
+                None => writeln!(
+                    f,
+                    "  {}: {} {sense} {}  {state}",
+                    check.name, check.activity, check.rhs
+                )?,
+            }
+        }
+        for (var, value) in &self.not_binary {
+            writeln!(f, "Not binary: {var} = {value}")?;
+        }
+        for var in &self.unknown {
+            writeln!(f, "Unknown variable: {var}")?;
+        }
+        let violations = self.violations().count();
+        if self.is_valid() {
+            write!(f, "The solution is valid")
+        } else {
+            write!(
+                f,
+                "The solution is NOT valid ({violations} violated constraints)"
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{write_json, write_sol, Balas, LpErrors, Model, SolutionValues};
+    use std::path::Path;
+
+    #[test]
+    fn verifies_solutions_in_both_formats() {
+        let path = Path::new("samples/demo.lp");
+        let result = Balas::from_lp(path).unwrap().solve();
+        let model = Model::from_lp(path).unwrap();
+        let dir = std::env::temp_dir();
+        for (name, json) in [("balas_verify.sol", false), ("balas_verify.json", true)] {
+            let mut out = vec![];
+            if json {
+                write_json(&result, &mut out).unwrap();
+            } else {
+                write_sol(&result, &mut out).unwrap();
+            }
+            let sol_path = dir.join(name);
+            std::fs::write(&sol_path, out).unwrap();
+            let solution = crate::read_values(&sol_path).unwrap();
+            assert_eq!(solution.objective, Some(11.0), "{name}");
+            assert!(model.verify(&solution).unwrap().is_valid(), "{name}");
+        }
+    }
+
+    #[test]
+    fn missing_values_are_errors() {
+        let model = Model::from_lp(Path::new("samples/demo.lp")).unwrap();
+        let solution = SolutionValues {
+            objective: None,
+            values: [("x1", 1.0), ("x2", 0.0), ("x4", 0.0)]
+                .map(|(var, value)| (var.to_string(), value))
+                .into(),
+        };
+        assert!(matches!(
+            model.verify(&solution),
+            Err(LpErrors::MissingValue(var)) if var == "x3"
+        ));
+    }
+}
@@ -0,0 +1,75 @@
+use crate::lp_errors::LpErrors;
+use crate::Balas;
+use num::Bounded;
+use std::collections::HashMap;
+use std::{fmt::Display, ops::Neg};
+
+impl<T> Balas<T>
+where
+    T: Bounded
+        + Neg<Output = T>
+        + Copy
+        + Display
+        + num::Zero
+        + num::ToPrimitive
+        + for<'a> std::ops::AddAssign<&'a T>
+        + for<'a> std::ops::SubAssign<&'a T>
+        + std::cmp::PartialOrd
+        + std::fmt::Debug,
+    Vec<T>: FromIterator<<T as Neg>::Output>,
+{
+    /// Start the search from a known solution, given as values of the original
+    /// variables.  The solution is checked against the constraints and the values of
+    /// any variables that `presolve` or the bounds fixed, and becomes the incumbent, so
+    /// the search only looks for something strictly better and still has an answer if
+    /// nothing better exists.  Returns the (original) objective value.
+    ///
+    /// Since `reset` clears the incumbent, call this after it.
+    pub fn warm_start(&mut self, assignment: &HashMap<String, u8>) -> Result<T, LpErrors> {
+        if let Some(name) = assignment.keys().find(|name| !self.vars.contains(name)) {
+            return Err(LpErrors::UnknownVariable(name.clone()));
+        }
+
+        let mut columns = Vec::with_capacity(self.vars.len());
+        for &original in &self.order {
+            let var = &self.vars[original];
+            let Some(&value) = assignment.get(var) else {
+                return Err(LpErrors::MissingValue(var.clone()));
+            };
+            // complementing is its own inverse
+            columns.push(self.transform.value(original, value));
+        }
+        // variables taken out of the columns can only have the value they were fixed to
+        for &(original, fixed) in &self.removed {
+            if let Some(&value) = assignment.get(&self.vars[original]) {
+                if value != self.transform.value(original, fixed) {
+                    return Err(LpErrors::InfeasibleStart);
+                }
+            }
+        }
+
+        let mut accumulator: Vec<T> = self.rhs.iter().map(|&b| -b).collect();
+        let mut objective = T::zero();
+        for (column, _) in columns.iter().enumerate().filter(|(_, &value)| value == 1) {
+            accumulator
+                .iter_mut()
+                .zip(&self.constraints[column])
+                .for_each(|(a, b)| *a += b);
+            objective += &self.coefficients[column];
+        }
+        if !Self::satisfied(&accumulator, &self.row_widths()) {
+            return Err(LpErrors::InfeasibleStart);
+        }
+
+        self.best = objective;
+        self.solution = columns;
+        Ok(self.transform.objective(objective))
+    }
+
+    /// Only search for solutions strictly better than `objective`, a value of the
+    /// original objective (from a heuristic, for example).  Unlike `warm_start`, there
+    /// is no solution to fall back on if nothing better is found.
+    pub fn set_bound(&mut self, objective: T) {
+        self.best = self.transform.solver_objective(objective);
+    }
+}